@@ -14,6 +14,7 @@ use sappix::Renderer;
 use sappix::Sprite;
 use sappix::SpriteFrameMode;
 use sappix::Triangle;
+use sappix::Vec2;
 
 const TEST_SPRITE_FILE: &[u8] = include_bytes!("../testimgs/space.gif");
 
@@ -63,28 +64,28 @@ fn main() {
         I16Vec2::splat(256),
         0,
         true,
-        FBColor::MAGENTA.with_a(0.5),
+        ColorMode::Solid(FBColor::MAGENTA.with_a(0.5)),
         BlendMode::Alpha,
     );
     let mut circle_2 = Circle::new(
         I16Vec2::splat(256),
         0,
         true,
-        FBColor::BLACK.with_a(0.5),
+        ColorMode::Solid(FBColor::BLACK.with_a(0.5)),
         BlendMode::Alpha,
     );
     let mut circle_3 = Circle::new(
         I16Vec2::splat(256),
         0,
         true,
-        FBColor::CYAN.with_a(0.5),
+        ColorMode::Solid(FBColor::CYAN.with_a(0.5)),
         BlendMode::Alpha,
     );
 
     let mut triangle = Triangle::new(
-        I16Vec2::new(128, 128),
-        I16Vec2::new(256 + 128, 128 + 64),
-        I16Vec2::new(128 + 64, 256),
+        Vec2::new(128.0, 128.0),
+        Vec2::new(256.0 + 128.0, 128.0 + 64.0),
+        Vec2::new(128.0 + 64.0, 256.0),
         ColorMode::PerPoint([FBColor::MAGENTA, FBColor::YELLOW, FBColor::CYAN]),
         BlendMode::Opaque,
     );
@@ -118,30 +119,28 @@ fn main() {
             as u16;
         // sprite.draw(&mut renderer);
 
-        triangle.a.x =
-            ((128 as f32) + ((i as f32 / FRAMECOUNT as f32) * 360.0).to_radians().cos()) as i16;
-        triangle.a.y =
-            ((128 as f32) + ((i as f32 / FRAMECOUNT as f32) * 360.0).to_radians().sin()) as i16;
-        triangle.b.x = (((256 + 128) as f32)
+        triangle.a.x = 128.0 + ((i as f32 / FRAMECOUNT as f32) * 360.0).to_radians().cos();
+        triangle.a.y = 128.0 + ((i as f32 / FRAMECOUNT as f32) * 360.0).to_radians().sin();
+        triangle.b.x = (256 + 128) as f32
             + (((i as f32 / FRAMECOUNT as f32) * 360.0 + 90.0)
                 .to_radians()
                 .cos())
-                * 16.0) as i16;
-        triangle.b.y = (((128 + 64) as f32)
+                * 16.0;
+        triangle.b.y = (128 + 64) as f32
             + (((i as f32 / FRAMECOUNT as f32) * 360.0 + 90.0)
                 .to_radians()
                 .sin())
-                * 12.0) as i16;
-        triangle.c.x = (((128 + 64) as f32)
+                * 12.0;
+        triangle.c.x = (128 + 64) as f32
             + (((i as f32 / FRAMECOUNT as f32) * 360.0 + 195.0)
                 .to_radians()
                 .cos())
-                * 13.0) as i16;
-        triangle.c.y = ((256 as f32)
+                * 13.0;
+        triangle.c.y = 256.0
             + (((i as f32 / FRAMECOUNT as f32) * 360.0 + 195.0)
                 .to_radians()
                 .sin())
-                * 24.0) as i16;
+                * 24.0;
         triangle.draw(&mut renderer);
 
         rect.rect.size.x =
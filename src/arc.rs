@@ -0,0 +1,339 @@
+use alloc::vec::Vec;
+
+use crate::ellipse::{draw_ellipse_rows, trace_ellipse};
+use crate::line::line_pixels;
+use crate::scalar::floor_i32;
+use crate::{
+    BlendMode, Blender, Drawable, FBColor, I16Vec2, PixelFormat, Renderer, Scalar, U16Vec2,
+};
+
+type Point<S> = [S; 2];
+
+fn cross<S: Scalar>(a: Point<S>, b: Point<S>) -> S {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+// length of the sweep from `start` to `end`, going clockwise on screen.
+// equal angles sweep nothing, while angles a multiple of full turn apart sweep everything
+fn sweep(start: i16, end: i16) -> i32 {
+    if start == end {
+        return 0;
+    }
+    match (end as i32 - start as i32).rem_euclid(256) {
+        0 => 256,
+        sweep => sweep,
+    }
+}
+
+/// Angles and points of a partial ellipse, relative to its center.
+struct EllipseSlice<S> {
+    radii: U16Vec2,
+    rotation: (S, S),
+    start_angle: i16,
+    sweep: i32,
+}
+impl<S: Scalar> EllipseSlice<S> {
+    fn new(radii: U16Vec2, rotation: i16, start_angle: i16, end_angle: i16) -> Self {
+        Self {
+            radii,
+            rotation: (S::from_ratio(rotation as i32, 256) * S::TAU).sin_cos(),
+            start_angle,
+            sweep: sweep(start_angle, end_angle),
+        }
+    }
+
+    fn rotate(&self, p: Point<S>) -> Point<S> {
+        let (sin, cos) = self.rotation;
+        [p[0] * cos - p[1] * sin, p[0] * sin + p[1] * cos]
+    }
+
+    fn direction(&self, angle: i32) -> Point<S> {
+        let (sin, cos) = (S::from_ratio(angle, 256) * S::TAU).sin_cos();
+        self.rotate([cos, sin])
+    }
+
+    // where the ray at `angle` leaves the ellipse
+    fn point(&self, angle: i32) -> Point<S> {
+        let (sin, cos) = (S::from_ratio(angle, 256) * S::TAU).sin_cos();
+        let k = S::from_i32(self.radii.x.max(self.radii.y) as i32);
+        let a = S::from_i32(self.radii.x as i32) / k;
+        let b = S::from_i32(self.radii.y as i32) / k;
+        let r = a * b / (b * b * cos * cos + a * a * sin * sin).sqrt() * k;
+        self.rotate([cos * r, sin * r])
+    }
+
+    fn end_angle(&self) -> i32 {
+        self.start_angle as i32 + self.sweep
+    }
+
+    fn in_wedge(&self, p: Point<S>) -> bool {
+        if self.sweep >= 256 {
+            return true;
+        }
+        let after_start = cross(self.direction(self.start_angle as i32), p) >= S::ZERO;
+        let before_end = cross(p, self.direction(self.end_angle())) >= S::ZERO;
+        if self.sweep <= 128 {
+            after_start && before_end
+        } else {
+            after_start || before_end
+        }
+    }
+}
+
+fn relative<S: Scalar>(center: I16Vec2, x: i32, y: i32) -> Point<S> {
+    [
+        S::from_i32(x - center.x as i32),
+        S::from_i32(y - center.y as i32),
+    ]
+}
+
+fn round<S: Scalar>(center: I16Vec2, p: Point<S>) -> I16Vec2 {
+    // offsets from centers near the edge of the i16 range can leave it
+    let round = |c: i16, v: S| {
+        (c as i32 + floor_i32(v + S::HALF)).clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    };
+    I16Vec2::new(round(center.x, p[0]), round(center.y, p[1]))
+}
+
+/// Part of an ellipse outline from `start_angle` to `end_angle`, going clockwise on
+/// screen from the x radius. Angles use 256 for a full turn.
+///
+/// Filled arcs are closed by the chord between their ends.
+pub struct EllipseArc<B = BlendMode> {
+    pub position: I16Vec2,
+    pub radii: U16Vec2,
+    pub rotation: i16,
+    pub start_angle: i16,
+    pub end_angle: i16,
+    pub fill: bool,
+    pub color: FBColor,
+    pub blend_mode: B,
+}
+impl<B: Blender> EllipseArc<B> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        position: I16Vec2,
+        radii: U16Vec2,
+        rotation: i16,
+        start_angle: i16,
+        end_angle: i16,
+        fill: bool,
+        color: FBColor,
+        blend_mode: B,
+    ) -> Self {
+        Self {
+            position,
+            radii,
+            rotation,
+            start_angle,
+            end_angle,
+            fill,
+            color,
+            blend_mode,
+        }
+    }
+}
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for EllipseArc<B> {
+    fn draw(&self, renderer: &mut Renderer<P, S>) {
+        let slice =
+            EllipseSlice::<S>::new(self.radii, self.rotation, self.start_angle, self.end_angle);
+        if self.radii.x == 0 || self.radii.y == 0 || slice.sweep == 0 {
+            return;
+        }
+        let rows = trace_ellipse(renderer, self.position, self.radii, self.rotation);
+        let color = renderer.decode_color(self.color);
+
+        if !self.fill {
+            draw_ellipse_rows(renderer, &rows, false, color, &self.blend_mode, |x, y| {
+                slice.in_wedge(relative(self.position, x, y))
+            });
+            return;
+        }
+
+        // keep the side of the chord that the middle of the arc is on
+        let start = slice.point(self.start_angle as i32);
+        let chord = {
+            let end = slice.point(slice.end_angle());
+            [end[0] - start[0], end[1] - start[1]]
+        };
+        let side = |p: Point<S>| cross(chord, [p[0] - start[0], p[1] - start[1]]);
+        let arc_side = side(slice.point(self.start_angle as i32 + slice.sweep / 2)) > S::ZERO;
+        draw_ellipse_rows(renderer, &rows, true, color, &self.blend_mode, |x, y| {
+            if slice.sweep >= 256 {
+                return true;
+            }
+            let side = side(relative(self.position, x, y));
+            if arc_side {
+                side >= S::ZERO
+            } else {
+                side <= S::ZERO
+            }
+        });
+    }
+}
+
+/// Wedge of an ellipse from `start_angle` to `end_angle`, with angles like [`EllipseArc`].
+///
+/// Outlined pies are drawn with lines from the center to both ends of their arc, and
+/// pixels where the lines and arc overlap are only drawn once.
+pub struct Pie<B = BlendMode> {
+    pub position: I16Vec2,
+    pub radii: U16Vec2,
+    pub rotation: i16,
+    pub start_angle: i16,
+    pub end_angle: i16,
+    pub fill: bool,
+    pub color: FBColor,
+    pub blend_mode: B,
+}
+impl<B: Blender> Pie<B> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        position: I16Vec2,
+        radii: U16Vec2,
+        rotation: i16,
+        start_angle: i16,
+        end_angle: i16,
+        fill: bool,
+        color: FBColor,
+        blend_mode: B,
+    ) -> Self {
+        Self {
+            position,
+            radii,
+            rotation,
+            start_angle,
+            end_angle,
+            fill,
+            color,
+            blend_mode,
+        }
+    }
+}
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for Pie<B> {
+    fn draw(&self, renderer: &mut Renderer<P, S>) {
+        let slice =
+            EllipseSlice::<S>::new(self.radii, self.rotation, self.start_angle, self.end_angle);
+        if self.radii.x == 0 || self.radii.y == 0 || slice.sweep == 0 {
+            return;
+        }
+        let rows = trace_ellipse(renderer, self.position, self.radii, self.rotation);
+        let color = renderer.decode_color(self.color);
+        let in_wedge = |x, y| slice.in_wedge(relative(self.position, x, y));
+        if self.fill || slice.sweep >= 256 {
+            draw_ellipse_rows(
+                renderer,
+                &rows,
+                self.fill,
+                color,
+                &self.blend_mode,
+                in_wedge,
+            );
+            return;
+        }
+
+        // the lines share their center pixel and can touch the arc, so their pixels
+        // are gathered first to blend each pixel of the outline once
+        let mut spokes = Vec::new();
+        for angle in [self.start_angle as i32, slice.end_angle()] {
+            let end = round(self.position, slice.point(angle));
+            line_pixels(
+                renderer,
+                self.position.as_vec2(),
+                end.as_vec2(),
+                &mut spokes,
+            );
+        }
+        spokes.sort_unstable();
+        spokes.dedup();
+        draw_ellipse_rows(renderer, &rows, false, color, &self.blend_mode, |x, y| {
+            in_wedge(x, y) && spokes.binary_search(&(x as i16, y as i16)).is_err()
+        });
+        for &(x, y) in &spokes {
+            renderer.set(x, y, color, &self.blend_mode);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Drawable, FBColor};
+    extern crate std;
+
+    use ::test::Bencher;
+    use core::ffi::*;
+    use std::random::Random;
+
+    use crate::{BlendMode, I16Vec2, Renderer, U16Vec2};
+
+    use super::{EllipseArc, Pie};
+
+    #[test]
+    fn spokes_near_the_edge_of_the_i16_range() {
+        let mut renderer = Renderer::new(16, 16);
+        // the spoke ends land past i16::MAX and i16::MIN
+        for (x, start_angle) in [(i16::MAX - 10, 0), (i16::MIN + 10, 128)] {
+            let pie = Pie::new(
+                I16Vec2::new(x, 8),
+                U16Vec2::new(200, 4),
+                0,
+                start_angle,
+                start_angle + 32,
+                false,
+                FBColor::WHITE,
+                BlendMode::Opaque,
+            );
+            pie.draw(&mut renderer);
+        }
+    }
+
+    #[bench]
+    fn our_arc(bencher: &mut Bencher) {
+        let mut renderer = test::black_box(Renderer::new(128, 128));
+        let mut rand = std::random::DefaultRandomSource;
+        let mut arc = EllipseArc::new(
+            I16Vec2::ZERO,
+            U16Vec2::ZERO,
+            0,
+            0,
+            0,
+            false,
+            FBColor::MAGENTA,
+            BlendMode::Opaque,
+        );
+        bencher.iter(|| {
+            arc.position.x = c_short::random(&mut rand);
+            arc.position.y = c_short::random(&mut rand);
+            arc.radii.x = c_ushort::random(&mut rand).clamp(0, 512);
+            arc.radii.y = c_ushort::random(&mut rand).clamp(0, 512);
+            arc.start_angle = c_short::random(&mut rand);
+            arc.end_angle = c_short::random(&mut rand);
+            arc.draw(&mut renderer)
+        })
+    }
+
+    #[bench]
+    fn our_fillpie(bencher: &mut Bencher) {
+        let mut renderer = test::black_box(Renderer::new(128, 128));
+        let mut rand = std::random::DefaultRandomSource;
+        let mut pie = Pie::new(
+            I16Vec2::ZERO,
+            U16Vec2::ZERO,
+            0,
+            0,
+            0,
+            true,
+            FBColor::MAGENTA,
+            BlendMode::Opaque,
+        );
+        bencher.iter(|| {
+            pie.position.x = c_short::random(&mut rand);
+            pie.position.y = c_short::random(&mut rand);
+            pie.radii.x = c_ushort::random(&mut rand).clamp(0, 512);
+            pie.radii.y = c_ushort::random(&mut rand).clamp(0, 512);
+            pie.start_angle = c_short::random(&mut rand);
+            pie.end_angle = c_short::random(&mut rand);
+            pie.draw(&mut renderer)
+        })
+    }
+}
@@ -0,0 +1,351 @@
+use crate::{FBColor, PixelFormat};
+
+/// How a drawable's colors are combined with the framebuffer.
+///
+/// Source colors are given with straight alpha, while the framebuffer holds
+/// premultiplied colors. For fully opaque framebuffers the two are identical.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BlendMode {
+    Opaque,
+    Alpha,
+    Add,
+    Subtract,
+    Multiply,
+    Screen,
+    Lighten,
+    Darken,
+    Overlay,
+    Composite(PorterDuff),
+}
+
+/// Porter-Duff compositing operators.
+///
+/// Like every blend mode, these only affect the pixels covered by the drawable.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PorterDuff {
+    Clear,
+    Src,
+    Dst,
+    SrcOver,
+    DstOver,
+    SrcIn,
+    DstIn,
+    SrcOut,
+    DstOut,
+    SrcAtop,
+    DstAtop,
+    Xor,
+}
+
+/// Combines a straight alpha source color with a premultiplied destination pixel.
+///
+/// Drawables are generic over their blender, so implementing this trait for a custom
+/// type gets its own monomorphized inner loops, without per-pixel dynamic dispatch.
+pub trait Blender {
+    fn blend(&self, src: FBColor, dst: &mut FBColor);
+
+    /// Blends directly into a framebuffer pixel.
+    ///
+    /// The default implementation converts the pixel to and from [`FBColor`];
+    /// override it to blend natively in a specific pixel format.
+    fn blend_pixel<P: PixelFormat>(&self, src: FBColor, dst: &mut P) {
+        P::blend_with(src, dst, |src, dst| self.blend(src, dst));
+    }
+}
+impl Blender for BlendMode {
+    fn blend(&self, src: FBColor, dst: &mut FBColor) {
+        get_blend_func(*self)(src, dst);
+    }
+
+    fn blend_pixel<P: PixelFormat>(&self, src: FBColor, dst: &mut P) {
+        match self {
+            BlendMode::Opaque => P::blend_none(src, dst),
+            BlendMode::Alpha => P::blend_alpha(src, dst),
+            _ => P::blend_with(src, dst, get_blend_func(*self)),
+        }
+    }
+}
+impl<B: Blender> Blender for &B {
+    fn blend(&self, src: FBColor, dst: &mut FBColor) {
+        (**self).blend(src, dst);
+    }
+
+    fn blend_pixel<P: PixelFormat>(&self, src: FBColor, dst: &mut P) {
+        (**self).blend_pixel(src, dst);
+    }
+}
+
+pub(crate) type BlendFunc = fn(src: FBColor, dst: &mut FBColor);
+
+pub(crate) const fn get_blend_func(blend_mode: BlendMode) -> BlendFunc {
+    match blend_mode {
+        BlendMode::Opaque => blend_none,
+        BlendMode::Alpha => blend_alpha,
+        BlendMode::Add => blend_add,
+        BlendMode::Subtract => blend_subtract,
+        BlendMode::Multiply => blend_multiply,
+        BlendMode::Screen => blend_screen,
+        BlendMode::Lighten => blend_lighten,
+        BlendMode::Darken => blend_darken,
+        BlendMode::Overlay => blend_overlay,
+        BlendMode::Composite(op) => match op {
+            PorterDuff::Clear => composite_clear,
+            PorterDuff::Src => composite_src,
+            PorterDuff::Dst => composite_dst,
+            PorterDuff::SrcOver => blend_alpha,
+            PorterDuff::DstOver => composite_dst_over,
+            PorterDuff::SrcIn => composite_src_in,
+            PorterDuff::DstIn => composite_dst_in,
+            PorterDuff::SrcOut => composite_src_out,
+            PorterDuff::DstOut => composite_dst_out,
+            PorterDuff::SrcAtop => composite_src_atop,
+            PorterDuff::DstAtop => composite_dst_atop,
+            PorterDuff::Xor => composite_xor,
+        },
+    }
+}
+
+pub(crate) fn blend_none(src: FBColor, dst: &mut FBColor) {
+    *dst = src.premultiplied();
+}
+
+pub(crate) fn blend_alpha(src: FBColor, dst: &mut FBColor) {
+    if src.a() >= 1.0 {
+        *dst = src;
+        return;
+    }
+    if src.a() <= 0.0 {
+        return;
+    }
+
+    let src_a = src.a();
+    let src_r = src.r() * src_a;
+    let src_g = src.g() * src_a;
+    let src_b = src.b() * src_a;
+    let dst_r = dst.r();
+    let dst_g = dst.g();
+    let dst_b = dst.b();
+    let dst_a = dst.a();
+    let r = src_r + dst_r * (1.0 - src_a);
+    let g = src_g + dst_g * (1.0 - src_a);
+    let b = src_b + dst_b * (1.0 - src_a);
+    let a = src_a + dst_a * (1.0 - src_a);
+    dst.set_r(r);
+    dst.set_g(g);
+    dst.set_b(b);
+    dst.set_a(a);
+}
+
+// applies a per-channel blend of the unpremultiplied source and destination colors,
+// then composites the result source-over, as specified by the w3c compositing spec
+fn blend_separable(src: FBColor, dst: &mut FBColor, blend: impl Fn(f32, f32) -> f32) {
+    let src_a = src.a();
+    if src_a <= 0.0 {
+        return;
+    }
+
+    let dst_a = dst.a();
+    let unpremultiplied = dst.unpremultiplied();
+    let channel = |s: f32, d_premultiplied: f32, d: f32| {
+        s * src_a * (1.0 - dst_a) + d_premultiplied * (1.0 - src_a) + src_a * dst_a * blend(s, d)
+    };
+    let r = channel(src.r(), dst.r(), unpremultiplied.r());
+    let g = channel(src.g(), dst.g(), unpremultiplied.g());
+    let b = channel(src.b(), dst.b(), unpremultiplied.b());
+    let a = src_a + dst_a * (1.0 - src_a);
+    dst.set_r(r);
+    dst.set_g(g);
+    dst.set_b(b);
+    dst.set_a(a);
+}
+
+pub(crate) fn blend_add(src: FBColor, dst: &mut FBColor) {
+    blend_separable(src, dst, |s, d| (s + d).min(1.0));
+}
+
+pub(crate) fn blend_subtract(src: FBColor, dst: &mut FBColor) {
+    blend_separable(src, dst, |s, d| (d - s).max(0.0));
+}
+
+pub(crate) fn blend_multiply(src: FBColor, dst: &mut FBColor) {
+    blend_separable(src, dst, |s, d| s * d);
+}
+
+pub(crate) fn blend_screen(src: FBColor, dst: &mut FBColor) {
+    blend_separable(src, dst, |s, d| s + d - s * d);
+}
+
+pub(crate) fn blend_lighten(src: FBColor, dst: &mut FBColor) {
+    blend_separable(src, dst, f32::max);
+}
+
+pub(crate) fn blend_darken(src: FBColor, dst: &mut FBColor) {
+    blend_separable(src, dst, f32::min);
+}
+
+pub(crate) fn blend_overlay(src: FBColor, dst: &mut FBColor) {
+    blend_separable(src, dst, |s, d| {
+        if d <= 0.5 {
+            2.0 * s * d
+        } else {
+            1.0 - 2.0 * (1.0 - s) * (1.0 - d)
+        }
+    });
+}
+
+// result = src * src_factor + dst * dst_factor, on premultiplied colors
+fn composite(src: FBColor, dst: &mut FBColor, factors: impl Fn(f32, f32) -> (f32, f32)) {
+    let src = src.premultiplied();
+    let (src_factor, dst_factor) = factors(src.a(), dst.a());
+    *dst = FBColor::new(
+        src.r() * src_factor + dst.r() * dst_factor,
+        src.g() * src_factor + dst.g() * dst_factor,
+        src.b() * src_factor + dst.b() * dst_factor,
+        src.a() * src_factor + dst.a() * dst_factor,
+    );
+}
+
+fn composite_clear(_src: FBColor, dst: &mut FBColor) {
+    *dst = FBColor::EMPTY;
+}
+
+fn composite_src(src: FBColor, dst: &mut FBColor) {
+    *dst = src.premultiplied();
+}
+
+fn composite_dst(_src: FBColor, _dst: &mut FBColor) {}
+
+fn composite_dst_over(src: FBColor, dst: &mut FBColor) {
+    composite(src, dst, |_, dst_a| (1.0 - dst_a, 1.0));
+}
+
+fn composite_src_in(src: FBColor, dst: &mut FBColor) {
+    composite(src, dst, |_, dst_a| (dst_a, 0.0));
+}
+
+fn composite_dst_in(src: FBColor, dst: &mut FBColor) {
+    composite(src, dst, |src_a, _| (0.0, src_a));
+}
+
+fn composite_src_out(src: FBColor, dst: &mut FBColor) {
+    composite(src, dst, |_, dst_a| (1.0 - dst_a, 0.0));
+}
+
+fn composite_dst_out(src: FBColor, dst: &mut FBColor) {
+    composite(src, dst, |src_a, _| (0.0, 1.0 - src_a));
+}
+
+fn composite_src_atop(src: FBColor, dst: &mut FBColor) {
+    composite(src, dst, |src_a, dst_a| (dst_a, 1.0 - src_a));
+}
+
+fn composite_dst_atop(src: FBColor, dst: &mut FBColor) {
+    composite(src, dst, |src_a, dst_a| (1.0 - dst_a, src_a));
+}
+
+fn composite_xor(src: FBColor, dst: &mut FBColor) {
+    composite(src, dst, |src_a, dst_a| (1.0 - dst_a, 1.0 - src_a));
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::{BlendMode, Blender, FBColor, PorterDuff};
+
+    fn blend(mode: BlendMode, src: FBColor, dst: FBColor) -> [f32; 4] {
+        let mut dst = dst;
+        mode.blend(src, &mut dst);
+        [dst.r(), dst.g(), dst.b(), dst.a()]
+    }
+
+    fn assert_color(actual: [f32; 4], expected: [f32; 4], what: &str) {
+        assert!(
+            actual
+                .iter()
+                .zip(&expected)
+                .all(|(a, e)| (a - e).abs() < 1e-6),
+            "{what}: {actual:?} isn't {expected:?}"
+        );
+    }
+
+    #[test]
+    fn separable_modes_on_opaque_pixels() {
+        let src = FBColor::new(0.5, 0.25, 1.0, 1.0);
+        let dst = FBColor::new(0.25, 0.75, 0.5, 1.0);
+        for (mode, expected) in [
+            (BlendMode::Add, [0.75, 1.0, 1.0, 1.0]),
+            (BlendMode::Subtract, [0.0, 0.5, 0.0, 1.0]),
+            (BlendMode::Multiply, [0.125, 0.1875, 0.5, 1.0]),
+            (BlendMode::Screen, [0.625, 0.8125, 1.0, 1.0]),
+            (BlendMode::Lighten, [0.5, 0.75, 1.0, 1.0]),
+            (BlendMode::Darken, [0.25, 0.25, 0.5, 1.0]),
+            (BlendMode::Overlay, [0.25, 0.625, 1.0, 1.0]),
+        ] {
+            assert_color(blend(mode, src, dst), expected, &std::format!("{mode:?}"));
+        }
+    }
+
+    #[test]
+    fn separable_modes_with_alpha() {
+        // half of the source blends with the destination, the other half lets it through
+        let dst = FBColor::new(0.5, 0.5, 0.5, 1.0);
+        assert_color(
+            blend(BlendMode::Multiply, FBColor::new(1.0, 0.0, 0.5, 0.5), dst),
+            [0.5, 0.25, 0.375, 1.0],
+            "translucent source",
+        );
+        // with nothing underneath, the source is drawn as is
+        assert_color(
+            blend(
+                BlendMode::Multiply,
+                FBColor::new(0.5, 0.25, 1.0, 1.0),
+                FBColor::EMPTY,
+            ),
+            [0.5, 0.25, 1.0, 1.0],
+            "empty destination",
+        );
+        // over a translucent destination, which is premultiplied
+        assert_color(
+            blend(
+                BlendMode::Screen,
+                FBColor::new(0.5, 0.5, 0.5, 0.5),
+                FBColor::new(0.25, 0.25, 0.25, 0.5),
+            ),
+            [0.4375, 0.4375, 0.4375, 0.75],
+            "translucent destination",
+        );
+        assert_color(
+            blend(BlendMode::Add, FBColor::new(1.0, 1.0, 1.0, 0.0), dst),
+            [0.5, 0.5, 0.5, 1.0],
+            "transparent source",
+        );
+    }
+
+    #[test]
+    fn porter_duff_operators() {
+        // straight red at 3/4 alpha over a premultiplied blue at 1/4 alpha
+        let src = FBColor::new(1.0, 0.0, 0.0, 0.75);
+        let dst = FBColor::new(0.0, 0.0, 0.25, 0.25);
+        for (op, expected) in [
+            (PorterDuff::Clear, [0.0, 0.0, 0.0, 0.0]),
+            (PorterDuff::Src, [0.75, 0.0, 0.0, 0.75]),
+            (PorterDuff::Dst, [0.0, 0.0, 0.25, 0.25]),
+            (PorterDuff::SrcOver, [0.75, 0.0, 0.0625, 0.8125]),
+            (PorterDuff::DstOver, [0.5625, 0.0, 0.25, 0.8125]),
+            (PorterDuff::SrcIn, [0.1875, 0.0, 0.0, 0.1875]),
+            (PorterDuff::DstIn, [0.0, 0.0, 0.1875, 0.1875]),
+            (PorterDuff::SrcOut, [0.5625, 0.0, 0.0, 0.5625]),
+            (PorterDuff::DstOut, [0.0, 0.0, 0.0625, 0.0625]),
+            (PorterDuff::SrcAtop, [0.1875, 0.0, 0.0625, 0.25]),
+            (PorterDuff::DstAtop, [0.5625, 0.0, 0.1875, 0.75]),
+            (PorterDuff::Xor, [0.5625, 0.0, 0.0625, 0.625]),
+        ] {
+            assert_color(
+                blend(BlendMode::Composite(op), src, dst),
+                expected,
+                &std::format!("{op:?}"),
+            );
+        }
+    }
+}
@@ -1,19 +1,29 @@
-use crate::{BlendMode, Drawable, FBColor, I16Vec2};
+use crate::scalar::ceil_i32;
+use crate::{
+    BlendMode, Blender, ColorMode, Drawable, FBColor, I16Vec2, PixelFormat, Scalar, StrokeAlignment,
+};
 
-pub struct Circle {
+pub struct Circle<B = BlendMode> {
     pub position: I16Vec2,
     pub radius: u16,
     pub fill: bool,
-    pub color: FBColor,
-    pub blend_mode: BlendMode,
+    /// Per point colors are a radial gradient, from the center to the rim.
+    pub color: ColorMode<2>,
+    pub blend_mode: B,
+    /// Scales the alpha of pixels on the edge by how much of them the circle covers.
+    pub anti_aliased: bool,
+    /// Outline width, when not filled. One pixel outlines that are inner or centered
+    /// trace the rim like the default outline, others are rings around it.
+    pub width: u16,
+    pub alignment: StrokeAlignment,
 }
-impl Circle {
+impl<B: Blender> Circle<B> {
     pub fn new(
         position: I16Vec2,
         radius: u16,
         fill: bool,
-        color: FBColor,
-        blend_mode: BlendMode,
+        color: ColorMode<2>,
+        blend_mode: B,
     ) -> Self {
         Self {
             position,
@@ -21,20 +31,122 @@ impl Circle {
             fill,
             color,
             blend_mode,
+            anti_aliased: false,
+            width: 1,
+            alignment: StrokeAlignment::Inner,
         }
     }
 
-    fn draw_internal<const FILL: bool>(&self, renderer: &mut crate::Renderer) {
+    fn colors<P: PixelFormat, S: Scalar>(
+        &self,
+        renderer: &crate::Renderer<P, S>,
+    ) -> ([FBColor; 2], bool) {
+        match self.color.map(|c| renderer.decode_color(c)) {
+            ColorMode::Solid(color) => ([color; 2], true),
+            ColorMode::PerPoint(colors) => (colors, false),
+        }
+    }
+
+    // `distance` from the center is relative to the radius
+    fn gradient<S: Scalar>(colors: &[FBColor; 2], distance: S) -> FBColor {
+        colors[0].lerp(colors[1], if distance > S::ONE { S::ONE } else { distance })
+    }
+
+    fn draw_anti_aliased<P: PixelFormat, S: Scalar, const FILL: bool>(
+        &self,
+        renderer: &mut crate::Renderer<P, S>,
+    ) {
         if self.radius == 0 {
             return;
         }
 
-        let render_width = renderer.width() as u16;
-        let render_height = renderer.height() as u16;
+        let (colors, single_color) = self.colors(renderer);
+        let clip_min = renderer.clip_min();
+        let clip_max = renderer.clip_max();
+        let center_x = self.position.x as i32;
+        let center_y = self.position.y as i32;
+        let radius = self.radius as i32;
+
+        // pixels are covered from half a pixel outside of the rim, and outlines
+        // until half a pixel inside of it. distances are relative to `outer`
+        let r = S::from_i32(radius);
+        let outer = r + S::HALF;
+        let inner = (r - S::HALF - S::ONE) / outer;
+
+        let min_y = (center_y - radius - 1).max(clip_min.y as i32);
+        let max_y = (center_y + radius + 2).min(clip_max.y as i32);
+        for y in min_y..max_y {
+            let dy = S::from_i32(y - center_y) / outer;
+            let rest = S::ONE - dy * dy;
+            if rest <= S::ZERO {
+                continue;
+            }
+            let half = ceil_i32(outer * rest.sqrt());
+            // no part of an outline reaches this far in
+            let skip = if !FILL && inner * inner > dy * dy {
+                (inner * outer * (S::ONE - dy * dy / (inner * inner)).sqrt()).to_i32()
+            } else {
+                0
+            };
+
+            let min_x = (center_x - half).max(clip_min.x as i32);
+            let max_x = (center_x + half + 1).min(clip_max.x as i32);
+            for x in min_x..max_x {
+                if (x - center_x).abs() < skip {
+                    continue;
+                }
+                let dx = S::from_i32(x - center_x) / outer;
+                let distance = (dx * dx + dy * dy).sqrt() * outer;
+                let coverage = if FILL {
+                    outer - distance
+                } else if distance > r {
+                    S::ONE - (distance - r)
+                } else {
+                    S::ONE - (r - distance)
+                };
+                if coverage <= S::ZERO {
+                    continue;
+                }
+                let color = if single_color {
+                    colors[0]
+                } else {
+                    Self::gradient(&colors, distance / r)
+                };
+                let color = if coverage < S::ONE {
+                    color.with_a(color.a() * coverage.to_f32())
+                } else {
+                    color
+                };
+                renderer.set_unchecked(x as i16, y as i16, color, &self.blend_mode);
+            }
+        }
+    }
+
+    fn draw_internal<P: PixelFormat, S: Scalar, const FILL: bool>(
+        &self,
+        renderer: &mut crate::Renderer<P, S>,
+    ) {
+        if self.radius == 0 {
+            return;
+        }
+
+        let clip_min = renderer.clip_min();
+        let clip_max = renderer.clip_max();
+        let clip_height = (clip_max.y - clip_min.y) as u16;
 
         let center_x = self.position.x;
         let center_y = self.position.y;
         let radius = self.radius as i16;
+        let (colors, single_color) = self.colors(renderer);
+        let color_at = |x: i16, y: i16| {
+            if single_color {
+                colors[0]
+            } else {
+                let dx = S::from_ratio((x - center_x) as i32, radius as i32);
+                let dy = S::from_ratio((y - center_y) as i32, radius as i32);
+                Self::gradient(&colors, (dx * dx + dy * dy).sqrt())
+            }
+        };
 
         let mut f = 1 - radius;
         let mut delta_x = 0;
@@ -47,7 +159,9 @@ impl Circle {
         let mut min_y = i16::MAX;
         let mut max_y = i16::MIN;
 
-        if (self.position.y as u16) < render_height {
+        if ((self.position.y - clip_min.y) as u16) < clip_height {
+            min_y = center_y;
+            max_y = center_y;
             edge_buf[self.position.y as usize] =
                 (self.position.x - radius)..(self.position.x + radius);
         }
@@ -62,22 +176,22 @@ impl Circle {
             delta_x += 2;
             f += delta_x + 1;
 
-            if ((center_y - y) as u16) < render_width {
+            if (((center_y - y) - clip_min.y) as u16) < clip_height {
                 min_y = min_y.min(center_y - y);
                 max_y = max_y.max(center_y - y);
                 edge_buf[(center_y - y) as usize] = (center_x - x)..(center_x + x)
             }
-            if ((center_y + y) as u16) < render_width {
+            if (((center_y + y) - clip_min.y) as u16) < clip_height {
                 min_y = min_y.min(center_y + y);
                 max_y = max_y.max(center_y + y);
                 edge_buf[(center_y + y) as usize] = (center_x - x)..(center_x + x);
             }
-            if ((center_y - x) as u16) < render_width {
+            if (((center_y - x) - clip_min.y) as u16) < clip_height {
                 min_y = min_y.min(center_y - x);
                 max_y = max_y.max(center_y - x);
                 edge_buf[(center_y - x) as usize] = (center_x - y)..(center_x + y);
             }
-            if ((center_y + x) as u16) < render_width {
+            if (((center_y + x) - clip_min.y) as u16) < clip_height {
                 min_y = min_y.min(center_y + x);
                 max_y = max_y.max(center_y + x);
                 edge_buf[(center_y + x) as usize] = (center_x - y)..(center_x + y);
@@ -85,47 +199,180 @@ impl Circle {
         }
 
         if FILL {
-            for y in min_y..max_y {
+            for y in min_y..=max_y {
                 let mut edge = renderer.edge_buffer()[y as usize].clone();
-                edge.start = edge.start.clamp(0, renderer.width() - 1);
-                edge.end = edge.end.clamp(0, renderer.width() - 1);
+                edge.start = edge.start.clamp(clip_min.x, clip_max.x);
+                edge.end = edge.end.clamp(clip_min.x, clip_max.x);
 
                 for x in edge {
-                    renderer.set(x, y, self.color, self.blend_mode);
+                    renderer.set_unchecked(x, y, color_at(x, y), &self.blend_mode);
                 }
             }
         } else {
-            for y in min_y..max_y {
+            for y in min_y..=max_y {
                 let edge = renderer.edge_buffer()[y as usize].clone();
-                renderer.set(edge.start, y, self.color, self.blend_mode);
-                renderer.set(edge.end, y, self.color, self.blend_mode);
+                renderer.set(edge.start, y, color_at(edge.start, y), &self.blend_mode);
+                renderer.set(edge.end, y, color_at(edge.end, y), &self.blend_mode);
+            }
+        }
+    }
+
+    // draws pixels between `inner` and `outer` distance from the center
+    fn draw_ring<P: PixelFormat, S: Scalar, const ANTI_ALIASED: bool>(
+        &self,
+        renderer: &mut crate::Renderer<P, S>,
+        outer: S,
+        inner: S,
+    ) {
+        if self.radius == 0 || outer <= S::ZERO {
+            return;
+        }
+
+        let (colors, single_color) = self.colors(renderer);
+        let clip_min = renderer.clip_min();
+        let clip_max = renderer.clip_max();
+        let center_x = self.position.x as i32;
+        let center_y = self.position.y as i32;
+        let r = S::from_i32(self.radius as i32);
+
+        // anti-aliased pixels are partially covered up to half a pixel past the
+        // edges. distances are relative to `reach` so narrow scalars don't overflow
+        let (reach, hole) = if ANTI_ALIASED {
+            (outer + S::HALF, inner - S::HALF)
+        } else {
+            (outer, inner)
+        };
+        let hole = hole / reach;
+        let extent = ceil_i32(reach);
+
+        let min_y = (center_y - extent).max(clip_min.y as i32);
+        let max_y = (center_y + extent + 1).min(clip_max.y as i32);
+        for y in min_y..max_y {
+            let dy = S::from_i32(y - center_y) / reach;
+            let rest = S::ONE - dy * dy;
+            if rest < S::ZERO {
+                continue;
+            }
+            let half = ceil_i32(reach * rest.sqrt());
+            // pixels this far in are inside of the hole
+            let skip = if hole > S::ZERO && hole * hole > dy * dy {
+                (reach * (hole * hole - dy * dy).sqrt()).to_i32()
+            } else {
+                0
+            };
+
+            let min_x = (center_x - half).max(clip_min.x as i32);
+            let max_x = (center_x + half + 1).min(clip_max.x as i32);
+            for x in min_x..max_x {
+                if (x - center_x).abs() < skip {
+                    continue;
+                }
+                let dx = S::from_i32(x - center_x) / reach;
+                let distance = (dx * dx + dy * dy).sqrt() * reach;
+                let coverage = if ANTI_ALIASED {
+                    let clamp = |v: S| {
+                        if v < S::ZERO {
+                            S::ZERO
+                        } else if v > S::ONE {
+                            S::ONE
+                        } else {
+                            v
+                        }
+                    };
+                    clamp(outer + S::HALF - distance) - clamp(inner + S::HALF - distance)
+                } else if distance <= outer && distance > inner {
+                    S::ONE
+                } else {
+                    S::ZERO
+                };
+                if coverage <= S::ZERO {
+                    continue;
+                }
+                let color = if single_color {
+                    colors[0]
+                } else {
+                    Self::gradient(&colors, distance / r)
+                };
+                let color = if coverage < S::ONE {
+                    color.with_a(color.a() * coverage.to_f32())
+                } else {
+                    color
+                };
+                renderer.set_unchecked(x as i16, y as i16, color, &self.blend_mode);
             }
         }
     }
 }
-impl Drawable for Circle {
-    fn draw(&self, renderer: &mut crate::Renderer) {
-        if self.fill {
-            self.draw_internal::<true>(renderer);
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for Circle<B> {
+    fn draw(&self, renderer: &mut crate::Renderer<P, S>) {
+        let (outer, inner) = self.alignment.offsets(self.width);
+        // one pixel outlines on the rim keep the midpoint ring
+        if self.fill || (outer, inner) == (0, -1) {
+            match (self.anti_aliased, self.fill) {
+                (false, true) => self.draw_internal::<P, S, true>(renderer),
+                (false, false) => self.draw_internal::<P, S, false>(renderer),
+                (true, true) => self.draw_anti_aliased::<P, S, true>(renderer),
+                (true, false) => self.draw_anti_aliased::<P, S, false>(renderer),
+            }
+            return;
+        }
+
+        // other outlines are offset from the edge of the filled circle's pixels, which
+        // reach half a pixel past its radius
+        let edge = S::from_i32(self.radius as i32) + S::HALF;
+        let (outer, inner) = (edge + S::from_i32(outer), edge + S::from_i32(inner));
+        if self.anti_aliased {
+            self.draw_ring::<P, S, true>(renderer, outer, inner);
         } else {
-            self.draw_internal::<false>(renderer);
+            self.draw_ring::<P, S, false>(renderer, outer, inner);
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Drawable, FBColor};
+    use crate::{ColorMode, Drawable, FBColor};
     extern crate std;
 
     use ::test::Bencher;
     use core::ffi::*;
     use std::random::Random;
 
-    use crate::{BlendMode, I16Vec2, Renderer, ffi::*};
+    use alloc::vec::Vec;
+
+    use crate::{BlendMode, I16Vec2, Rect, Renderer, ffi::*};
 
     use super::Circle;
 
+    #[test]
+    fn one_row_clip() {
+        for fill in [false, true] {
+            let circle = Circle::new(
+                I16Vec2::new(8, 8),
+                4,
+                fill,
+                ColorMode::Solid(FBColor::WHITE),
+                BlendMode::Opaque,
+            );
+            let mut whole = Renderer::new(16, 16);
+            circle.draw(&mut whole);
+            // a clip covering only the center row still draws that row
+            let mut clipped = Renderer::new(16, 16);
+            clipped.push_clip(Rect::new(I16Vec2::new(0, 8), I16Vec2::new(16, 1)));
+            circle.draw(&mut clipped);
+
+            for (y, (whole, clipped)) in whole.rows().zip(clipped.rows()).enumerate() {
+                let drawn = |row: &[FBColor]| row.iter().map(|p| p.a() > 0.0).collect::<Vec<_>>();
+                if y == 8 {
+                    assert!(drawn(whole).contains(&true));
+                    assert_eq!(drawn(clipped), drawn(whole), "fill: {fill}");
+                } else {
+                    assert!(!drawn(clipped).contains(&true), "fill: {fill}, row {y}");
+                }
+            }
+        }
+    }
+
     #[bench]
     fn bmp_circle(bencher: &mut Bencher) {
         let b = test::black_box(unsafe { bm_create(128, 128) });
@@ -147,7 +394,13 @@ mod tests {
     fn our_circle(bencher: &mut Bencher) {
         let mut renderer = test::black_box(Renderer::new(128, 128));
         let mut rand = std::random::DefaultRandomSource;
-        let mut circle = Circle::new(I16Vec2::ZERO, 0, false, FBColor::MAGENTA, BlendMode::Opaque);
+        let mut circle = Circle::new(
+            I16Vec2::ZERO,
+            0,
+            false,
+            ColorMode::Solid(FBColor::MAGENTA),
+            BlendMode::Opaque,
+        );
         bencher.iter(|| {
             circle.position.x = c_short::random(&mut rand);
             circle.position.y = c_short::random(&mut rand);
@@ -177,7 +430,13 @@ mod tests {
     fn our_fillcircle(bencher: &mut Bencher) {
         let mut renderer = test::black_box(Renderer::new(128, 128));
         let mut rand = std::random::DefaultRandomSource;
-        let mut circle = Circle::new(I16Vec2::ZERO, 0, true, FBColor::MAGENTA, BlendMode::Opaque);
+        let mut circle = Circle::new(
+            I16Vec2::ZERO,
+            0,
+            true,
+            ColorMode::Solid(FBColor::MAGENTA),
+            BlendMode::Opaque,
+        );
         bencher.iter(|| {
             circle.position.x = c_short::random(&mut rand);
             circle.position.y = c_short::random(&mut rand);
@@ -1,12 +1,38 @@
 use core::ops::Mul;
 
-use glam::Vec4;
+use glam::{Vec3, Vec4};
+
+use crate::Scalar;
 
 #[derive(Clone, Copy)]
 pub enum ColorMode<const N: usize> {
     Solid(FBColor),
     PerPoint([FBColor; N]),
 }
+impl<const N: usize> ColorMode<N> {
+    pub(crate) fn map(self, f: impl Fn(FBColor) -> FBColor) -> Self {
+        match self {
+            ColorMode::Solid(c) => ColorMode::Solid(f(c)),
+            ColorMode::PerPoint(cs) => ColorMode::PerPoint(cs.map(f)),
+        }
+    }
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
 
 #[derive(Clone, Copy)]
 pub struct FBColor {
@@ -101,7 +127,7 @@ impl FBColor {
     }
 
     pub fn set_a(&mut self, value: f32) {
-        self.internal.z = value;
+        self.internal.w = value;
     }
 
     pub fn with_a(&self, a: f32) -> Self {
@@ -110,17 +136,77 @@ impl FBColor {
         }
     }
 
-    pub fn lerp(&self, rhs: Self, by: f32) -> Self {
+    /// Decodes sRGB encoded color channels to linear light. Alpha is left as is.
+    pub fn srgb_to_linear(&self) -> Self {
+        Self::new(
+            srgb_to_linear(self.r()),
+            srgb_to_linear(self.g()),
+            srgb_to_linear(self.b()),
+            self.a(),
+        )
+    }
+
+    /// Encodes linear light color channels to sRGB. Alpha is left as is.
+    pub fn linear_to_srgb(&self) -> Self {
+        Self::new(
+            linear_to_srgb(self.r()),
+            linear_to_srgb(self.g()),
+            linear_to_srgb(self.b()),
+            self.a(),
+        )
+    }
+
+    pub fn premultiplied(&self) -> Self {
+        Self {
+            internal: (self.internal.truncate() * self.internal.w).extend(self.internal.w),
+        }
+    }
+
+    pub fn unpremultiplied(&self) -> Self {
+        if self.internal.w <= 0.0 {
+            return Self::EMPTY;
+        }
+        Self {
+            internal: (self.internal.truncate() / self.internal.w).extend(self.internal.w),
+        }
+    }
+
+    pub fn lerp<S: Scalar>(&self, rhs: Self, by: S) -> Self {
+        Self {
+            internal: self.internal.lerp(rhs.internal, by.to_f32()),
+        }
+    }
+
+    /// Catmull-Rom spline through `colors`, from `colors[1]` at 0 to `colors[2]` at 1.
+    /// It can overshoot past both.
+    pub(crate) fn catmull_rom<S: Scalar>(colors: &[Self; 4], by: S) -> Self {
+        let t = by.to_f32();
+        let (t2, t3) = (t * t, t * t * t);
+        Self {
+            internal: colors[0].internal * ((-t3 + 2.0 * t2 - t) * 0.5)
+                + colors[1].internal * ((3.0 * t3 - 5.0 * t2 + 2.0) * 0.5)
+                + colors[2].internal * ((-3.0 * t3 + 4.0 * t2 + t) * 0.5)
+                + colors[3].internal * ((t3 - t2) * 0.5),
+        }
+    }
+
+    /// Clamps a premultiplied color back into range, with no channel above alpha.
+    pub(crate) fn clamp_premultiplied(&self) -> Self {
+        let a = self.internal.w.clamp(0.0, 1.0);
         Self {
-            internal: self.internal.lerp(rhs.internal, by),
+            internal: self
+                .internal
+                .truncate()
+                .clamp(Vec3::ZERO, Vec3::splat(a))
+                .extend(a),
         }
     }
 
-    pub fn lerp3(colors: &[Self; 3], weights: &[f32; 3]) -> Self {
+    pub fn lerp3<S: Scalar>(colors: &[Self; 3], weights: &[S; 3]) -> Self {
         Self {
-            internal: colors[0].internal * weights[0]
-                + colors[1].internal * weights[1]
-                + colors[2].internal * weights[2],
+            internal: colors[0].internal * weights[0].to_f32()
+                + colors[1].internal * weights[1].to_f32()
+                + colors[2].internal * weights[2].to_f32(),
         }
     }
 }
@@ -133,3 +219,19 @@ impl Mul for FBColor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::FBColor;
+
+    #[test]
+    fn srgb_round_trip() {
+        for i in 0..=255 {
+            let v = i as f32 / 255.0;
+            let color = FBColor::new(v, v, v, 0.5);
+            let linear = color.srgb_to_linear();
+            assert!(linear.r() <= v && linear.a() == 0.5);
+            assert!((linear.linear_to_srgb().r() - v).abs() < 1e-5);
+        }
+    }
+}
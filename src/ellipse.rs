@@ -0,0 +1,358 @@
+use crate::scalar::{ceil_i32, floor_i32};
+use crate::{
+    BlendMode, Blender, Drawable, FBColor, I16Vec2, PixelFormat, Renderer, Scalar, U16Vec2,
+};
+
+pub struct Ellipse<B = BlendMode> {
+    pub position: I16Vec2,
+    pub radii: U16Vec2,
+    /// Rotation of the x radius, where 256 is a full turn like [`crate::Sprite::rotation`].
+    pub rotation: i16,
+    pub fill: bool,
+    pub color: FBColor,
+    pub blend_mode: B,
+}
+impl<B: Blender> Ellipse<B> {
+    pub fn new(
+        position: I16Vec2,
+        radii: U16Vec2,
+        rotation: i16,
+        fill: bool,
+        color: FBColor,
+        blend_mode: B,
+    ) -> Self {
+        Self {
+            position,
+            radii,
+            rotation,
+            fill,
+            color,
+            blend_mode,
+        }
+    }
+}
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for Ellipse<B> {
+    fn draw(&self, renderer: &mut Renderer<P, S>) {
+        if self.radii.x == 0 || self.radii.y == 0 {
+            return;
+        }
+        let rows = trace_ellipse(renderer, self.position, self.radii, self.rotation);
+        let color = renderer.decode_color(self.color);
+        draw_ellipse_rows(
+            renderer,
+            &rows,
+            self.fill,
+            color,
+            &self.blend_mode,
+            |_, _| true,
+        );
+    }
+}
+
+/// Rows of an ellipse traced into the renderer's edge buffer.
+pub(crate) struct EllipseRows {
+    min_y: i32,
+    max_y: i32,
+    // inclusive spans of the rows just outside the clip rect, so outlines
+    // can tell which pixels on the clip edges are inside the ellipse
+    above: Option<(i32, i32)>,
+    below: Option<(i32, i32)>,
+}
+impl EllipseRows {
+    fn span<P: PixelFormat, S: Scalar>(
+        &self,
+        renderer: &Renderer<P, S>,
+        y: i32,
+    ) -> Option<(i32, i32)> {
+        if y < self.min_y {
+            self.above
+        } else if y >= self.max_y {
+            self.below
+        } else {
+            let edge = &renderer.edge_buffer()[y as usize];
+            (edge.start < edge.end).then(|| (edge.start as i32, edge.end as i32 - 1))
+        }
+    }
+}
+
+// midpoint ellipse algorithm, calling `plot` with the outermost x offset for every y
+// offset in the first quadrant. decisions grow with the fourth power of the radii, up
+// to about 4a²b², so they fit in an i64 for radii below 2^15 and need an i128 above
+macro_rules! walk_ellipse {
+    ($($name:ident: $int:ty),+) => {
+        $(
+            fn $name(rx: $int, ry: $int, mut plot: impl FnMut(i32, i32)) {
+                let (a2, b2) = (rx * rx, ry * ry);
+                let mut x = 0;
+                let mut y = ry;
+
+                // region 1, where the slope is shallower than -1. decisions are scaled by 4
+                let mut d = 4 * b2 - 4 * a2 * ry + a2;
+                while b2 * x < a2 * y {
+                    plot(x as i32, y as i32);
+                    if d >= 0 {
+                        d -= 8 * a2 * (y - 1);
+                        y -= 1;
+                    }
+                    d += 4 * b2 * (2 * x + 3);
+                    x += 1;
+                }
+
+                // region 2
+                let mut d =
+                    b2 * (2 * x + 1) * (2 * x + 1) + 4 * a2 * (y - 1) * (y - 1) - 4 * a2 * b2;
+                while y >= 0 {
+                    plot(x as i32, y as i32);
+                    if d <= 0 {
+                        d += 8 * b2 * (x + 1);
+                        x += 1;
+                    }
+                    d += 4 * a2 * (3 - 2 * y);
+                    y -= 1;
+                }
+            }
+        )+
+    };
+}
+
+walk_ellipse!(walk_ellipse_i64: i64, walk_ellipse_i128: i128);
+
+/// Traces the spans of an ellipse into the renderer's edge buffer, for the rows inside
+/// the clip rect. Axis aligned ellipses use the midpoint algorithm like [`crate::Circle`],
+/// rotated ones solve for each row's span.
+pub(crate) fn trace_ellipse<P: PixelFormat, S: Scalar>(
+    renderer: &mut Renderer<P, S>,
+    center: I16Vec2,
+    radii: U16Vec2,
+    rotation: i16,
+) -> EllipseRows {
+    let clip_min = renderer.clip_min();
+    let clip_max = renderer.clip_max();
+    let first = clip_min.y as i32 - 1;
+    let last = clip_max.y as i32;
+    let cx = center.x as i32;
+    let cy = center.y as i32;
+
+    let mut rows = EllipseRows {
+        min_y: i32::MAX,
+        max_y: i32::MIN,
+        above: None,
+        below: None,
+    };
+    let edge_buf = renderer.edge_buffer_mut();
+    let mut store = |y: i32, span: Option<(i32, i32)>| {
+        if y == first {
+            rows.above = span;
+        } else if y == last {
+            rows.below = span;
+        } else if first < y && y < last {
+            edge_buf[y as usize] = match span {
+                Some((l, r)) => {
+                    (l.clamp(i16::MIN as i32, i16::MAX as i32) as i16)
+                        ..((r + 1).clamp(i16::MIN as i32, i16::MAX as i32) as i16)
+                }
+                None => 0..0,
+            };
+            rows.min_y = rows.min_y.min(y);
+            rows.max_y = rows.max_y.max(y + 1);
+        }
+    };
+
+    let axis_radii = match rotation.rem_euclid(256) {
+        0 | 128 => Some((radii.x, radii.y)),
+        64 | 192 => Some((radii.y, radii.x)),
+        _ => None,
+    };
+    if let Some((rx, ry)) = axis_radii {
+        let plot = |x, y| {
+            store(cy - y, Some((cx - x, cx + x)));
+            store(cy + y, Some((cx - x, cx + x)));
+        };
+        if rx < 1 << 15 && ry < 1 << 15 {
+            walk_ellipse_i64(rx as i64, ry as i64, plot);
+        } else {
+            walk_ellipse_i128(rx as i128, ry as i128, plot);
+        }
+        return rows;
+    }
+
+    let (sin, cos) = (S::from_ratio(rotation as i32, 256) * S::TAU).sin_cos();
+    // work relative to the larger radius, which keeps values small for narrow scalars
+    let k = S::from_i32(radii.x.max(radii.y) as i32);
+    let a2 = S::from_i32(radii.x as i32) / k * (S::from_i32(radii.x as i32) / k);
+    let b2 = S::from_i32(radii.y as i32) / k * (S::from_i32(radii.y as i32) / k);
+    let extent = ceil_i32(k * (a2 * sin * sin + b2 * cos * cos).sqrt());
+
+    // solves b²(x cos + y sin)² + a²(y cos - x sin)² = a²b² for x
+    let two = S::from_i32(2);
+    let qa = b2 * cos * cos + a2 * sin * sin;
+    for dy in (first - cy).max(-extent)..=(last - cy).min(extent) {
+        let y = S::from_i32(dy) / k;
+        let qb = two * y * cos * sin * (b2 - a2);
+        let qc = y * y * (b2 * sin * sin + a2 * cos * cos) - a2 * b2;
+        let discriminant = qb * qb - two * two * qa * qc;
+        let span = if discriminant < S::ZERO {
+            None
+        } else {
+            let root = discriminant.sqrt();
+            let left = ceil_i32((-qb - root) / (two * qa) * k);
+            let right = floor_i32((-qb + root) / (two * qa) * k);
+            (left <= right).then_some((cx + left, cx + right))
+        };
+        store(cy + dy, span);
+    }
+    rows
+}
+
+/// Fills or outlines traced ellipse rows, skipping pixels `include` rejects.
+pub(crate) fn draw_ellipse_rows<P: PixelFormat, S: Scalar, B: Blender>(
+    renderer: &mut Renderer<P, S>,
+    rows: &EllipseRows,
+    fill: bool,
+    color: FBColor,
+    blend_mode: &B,
+    include: impl Fn(i32, i32) -> bool,
+) {
+    let clip_min = renderer.clip_min().x as i32;
+    let clip_max = renderer.clip_max().x as i32;
+    let plot = |renderer: &mut Renderer<P, S>, y: i32, left: i32, right: i32| {
+        for x in left.max(clip_min)..=right.min(clip_max - 1) {
+            if include(x, y) {
+                renderer.set_unchecked(x as i16, y as i16, color, blend_mode);
+            }
+        }
+    };
+
+    for y in rows.min_y..rows.max_y {
+        let Some((left, right)) = rows.span(renderer, y) else {
+            continue;
+        };
+        if fill {
+            plot(renderer, y, left, right);
+            continue;
+        }
+
+        // the outline is every pixel without all four neighbours inside the ellipse
+        let (inner_left, inner_right) =
+            match (rows.span(renderer, y - 1), rows.span(renderer, y + 1)) {
+                (Some(up), Some(down)) => (
+                    (left + 1).max(up.0).max(down.0),
+                    (right - 1).min(up.1).min(down.1),
+                ),
+                _ => (right + 1, right),
+            };
+        if inner_left > inner_right {
+            plot(renderer, y, left, right);
+        } else {
+            plot(renderer, y, left, inner_left - 1);
+            plot(renderer, y, inner_right + 1, right);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Drawable, FBColor};
+    extern crate std;
+
+    use ::test::Bencher;
+    use core::ffi::*;
+    use std::random::Random;
+
+    use crate::{BlendMode, I16Vec2, Renderer, U16Vec2, ffi::*};
+
+    use alloc::vec::Vec;
+
+    use super::{Ellipse, walk_ellipse_i64, walk_ellipse_i128};
+
+    #[test]
+    fn i64_walk_matches_i128() {
+        for (rx, ry) in [
+            (1, 1),
+            (5, 3),
+            (0x7fff, 0x7fff),
+            (0x7fff, 100),
+            (12345, 0x7ffe),
+        ] {
+            let (mut narrow, mut wide) = (Vec::new(), Vec::new());
+            walk_ellipse_i64(rx, ry, |x, y| narrow.push((x, y)));
+            walk_ellipse_i128(rx as i128, ry as i128, |x, y| wide.push((x, y)));
+            assert_eq!(narrow, wide, "radii {rx}, {ry}");
+            assert_eq!(narrow.last(), Some(&(rx as i32, 0)));
+        }
+    }
+
+    #[bench]
+    fn bmp_ellipse(bencher: &mut Bencher) {
+        let b = test::black_box(unsafe { bm_create(128, 128) });
+        let mut rand = std::random::DefaultRandomSource;
+        bencher.iter(|| unsafe {
+            let x = c_short::random(&mut rand) as i32;
+            let y = c_short::random(&mut rand) as i32;
+            let rx = (c_short::random(&mut rand) as i32).clamp(0, 512);
+            let ry = (c_short::random(&mut rand) as i32).clamp(0, 512);
+            bm_ellipse(b, x - rx, y - ry, x + rx, y + ry);
+        });
+        unsafe {
+            bm_free(b);
+        }
+    }
+
+    #[bench]
+    fn our_ellipse(bencher: &mut Bencher) {
+        let mut renderer = test::black_box(Renderer::new(128, 128));
+        let mut rand = std::random::DefaultRandomSource;
+        let mut ellipse = Ellipse::new(
+            I16Vec2::ZERO,
+            U16Vec2::ZERO,
+            0,
+            false,
+            FBColor::MAGENTA,
+            BlendMode::Opaque,
+        );
+        bencher.iter(|| {
+            ellipse.position.x = c_short::random(&mut rand);
+            ellipse.position.y = c_short::random(&mut rand);
+            ellipse.radii.x = c_ushort::random(&mut rand).clamp(0, 512);
+            ellipse.radii.y = c_ushort::random(&mut rand).clamp(0, 512);
+            ellipse.draw(&mut renderer)
+        })
+    }
+
+    #[bench]
+    fn bmp_fillellipse(bencher: &mut Bencher) {
+        let b = test::black_box(unsafe { bm_create(128, 128) });
+        let mut rand = std::random::DefaultRandomSource;
+        bencher.iter(|| unsafe {
+            let x = c_short::random(&mut rand) as i32;
+            let y = c_short::random(&mut rand) as i32;
+            let rx = (c_short::random(&mut rand) as i32).clamp(0, 512);
+            let ry = (c_short::random(&mut rand) as i32).clamp(0, 512);
+            bm_fillellipse(b, x - rx, y - ry, x + rx, y + ry);
+        });
+        unsafe {
+            bm_free(b);
+        }
+    }
+
+    #[bench]
+    fn our_fillellipse(bencher: &mut Bencher) {
+        let mut renderer = test::black_box(Renderer::new(128, 128));
+        let mut rand = std::random::DefaultRandomSource;
+        let mut ellipse = Ellipse::new(
+            I16Vec2::ZERO,
+            U16Vec2::ZERO,
+            0,
+            true,
+            FBColor::MAGENTA,
+            BlendMode::Opaque,
+        );
+        bencher.iter(|| {
+            ellipse.position.x = c_short::random(&mut rand);
+            ellipse.position.y = c_short::random(&mut rand);
+            ellipse.radii.x = c_ushort::random(&mut rand).clamp(0, 512);
+            ellipse.radii.y = c_ushort::random(&mut rand).clamp(0, 512);
+            ellipse.draw(&mut renderer)
+        })
+    }
+}
@@ -7,45 +7,84 @@ extern crate alloc;
 #[cfg(debug_assertions)]
 extern crate std;
 
+mod arc;
+mod blend;
 mod circle;
 mod color;
+mod ellipse;
 mod ffi;
 mod line;
+mod mesh;
+mod path;
+mod pixel;
 mod point;
+mod polygon;
+mod polyline;
 mod rect;
 mod renderer;
+mod scalar;
 mod sprite;
+mod stroke;
+mod svg;
+mod texture;
 mod triangle;
 
+pub use glam::Affine2;
 pub use glam::I16Vec2;
 pub use glam::U16Vec2;
+pub use glam::Vec2;
 
+pub use self::arc::EllipseArc;
+pub use self::arc::Pie;
+pub use self::blend::BlendMode;
+pub use self::blend::Blender;
+pub use self::blend::PorterDuff;
 pub use self::circle::Circle;
 pub use self::color::ColorMode;
 pub use self::color::FBColor;
+pub use self::ellipse::Ellipse;
 pub use self::line::Line;
+pub use self::mesh::Mesh;
+pub use self::mesh::MeshVertex;
+pub use self::path::FilledPath;
+pub use self::path::Path;
+pub use self::path::StrokedPath;
+pub use self::pixel::Argb1555;
+pub use self::pixel::Bgra8;
+pub use self::pixel::L8;
+pub use self::pixel::PixelFormat;
+pub use self::pixel::Rgb565;
+pub use self::pixel::Rgba8;
 pub use self::point::ColorVec2;
+pub use self::polygon::FillRule;
+pub use self::polygon::Polygon;
+pub use self::polyline::Polyline;
 pub use self::rect::ColorRect;
 pub use self::rect::Rect;
+pub use self::renderer::BlendSpace;
 pub use self::renderer::Renderer;
+pub use self::scalar::Fixed16;
+pub use self::scalar::Scalar;
+pub use self::sprite::ScanlineTransform;
 pub use self::sprite::Sprite;
 pub use self::sprite::SpriteFrame;
 pub use self::sprite::SpriteFrameMode;
+pub use self::stroke::LineCap;
+pub use self::stroke::LineJoin;
+pub use self::stroke::StrokeAlignment;
+pub use self::svg::SvgPathError;
+pub use self::texture::Texture;
+pub use self::texture::TextureFilter;
+pub use self::triangle::TexturedTriangle;
 pub use self::triangle::Triangle;
 
-pub trait Drawable {
-    fn draw(&self, renderer: &mut Renderer);
+pub trait Drawable<P: PixelFormat = FBColor, S: Scalar = f32> {
+    fn draw(&self, renderer: &mut Renderer<P, S>);
 }
 
-#[derive(Clone, Copy, Eq, PartialEq)]
-pub enum BlendMode {
-    Opaque,
-    Alpha,
-}
-
-pub(crate) fn bilinear_4_colors(
-    x: f32,
-    y: f32,
+pub(crate) fn bilinear_4_colors<S: Scalar>(
+    x: S,
+    y: S,
     top_left: FBColor,
     top_right: FBColor,
     bottom_left: FBColor,
@@ -55,34 +94,3 @@ pub(crate) fn bilinear_4_colors(
     let bottom_linear = bottom_left.lerp(bottom_right, x);
     top_linear.lerp(bottom_linear, y)
 }
-
-pub(crate) fn blend_none(src: FBColor, dst: &mut FBColor) {
-    *dst = src;
-}
-
-pub(crate) fn blend_alpha(src: FBColor, dst: &mut FBColor) {
-    if src.a() >= 1.0 {
-        *dst = src;
-        return;
-    }
-    if src.a() <= 0.0 {
-        return;
-    }
-
-    let src_r = src.r();
-    let src_g = src.g();
-    let src_b = src.b();
-    let src_a = src.a();
-    let dst_r = dst.r();
-    let dst_g = dst.g();
-    let dst_b = dst.b();
-    let dst_a = dst.a();
-    let r = src_r + dst_r * (1.0 - src_a);
-    let g = src_g + dst_g * (1.0 - src_a);
-    let b = src_b + dst_b * (1.0 - src_a);
-    let a = src_a + dst_a * (1.0 - src_a);
-    dst.set_r(r);
-    dst.set_g(g);
-    dst.set_b(b);
-    dst.set_a(a);
-}
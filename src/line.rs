@@ -1,191 +1,320 @@
-use crate::{BlendMode, ColorMode, Drawable, I16Vec2, Renderer};
+use alloc::vec::Vec;
+use core::ops::Range;
 
-pub struct Line {
-    pub a: I16Vec2,
-    pub b: I16Vec2,
+use crate::scalar::{SUBPIXEL, to_subpixel};
+use crate::stroke::{direction, fill_pieces, stroke_polyline};
+use crate::{
+    BlendMode, Blender, ColorMode, Drawable, FBColor, LineCap, LineJoin, PixelFormat, Renderer,
+    Scalar, Vec2,
+};
+
+/// End points are in pixels, with whole numbers at pixel centers. Fractional positions
+/// are rounded to a sixteenth of a pixel.
+pub struct Line<B = BlendMode> {
+    pub a: Vec2,
+    pub b: Vec2,
     pub color: ColorMode<2>,
-    pub blend_mode: BlendMode,
+    pub blend_mode: B,
+    /// Draws the line with Xiaolin Wu's algorithm, spreading each step's coverage over
+    /// the two nearest pixels. Coverage scales the color's alpha before blending.
+    pub anti_aliased: bool,
+    /// Lines wider than one pixel are filled as a quad around the line, with `cap`
+    /// added at both ends. They're never anti-aliased.
+    pub width: u16,
+    pub cap: LineCap,
 }
-impl Line {
-    pub const fn new(a: I16Vec2, b: I16Vec2, color: ColorMode<2>, blend_mode: BlendMode) -> Self {
+impl<B: Blender> Line<B> {
+    pub const fn new(a: Vec2, b: Vec2, color: ColorMode<2>, blend_mode: B) -> Self {
         Self {
             a,
             b,
             color,
             blend_mode,
+            anti_aliased: false,
+            width: 1,
+            cap: LineCap::Butt,
         }
     }
 }
-impl Drawable for Line {
-    fn draw(&self, renderer: &mut crate::Renderer) {
-        fn plot_line_common<const HIGH: bool>(
-            renderer: &mut Renderer,
-            x1: i16,
-            y1: i16,
-            x2: i16,
-            y2: i16,
-            color: ColorMode<2>,
-            blend_mode: BlendMode,
-        ) {
-            if y1 == y2 {
-                match color {
-                    ColorMode::Solid(color) => {
-                        for x in x1..x2 {
-                            renderer.set(x, y1, color, blend_mode);
-                        }
-                    }
-                    ColorMode::PerPoint([color_a, color_b]) => {
-                        for x in x1..x2 {
-                            let by = x as f32 / ((x2 - x1) as f32);
-                            renderer.set(x, y1, color_a.lerp(color_b, by), blend_mode);
-                        }
-                    }
-                }
-            }
-
-            if x1 == x2 {
-                match color {
-                    ColorMode::Solid(color) => {
-                        for y in y1..y2 {
-                            renderer.set(x1, y, color, blend_mode);
-                        }
-                    }
-                    ColorMode::PerPoint([color_a, color_b]) => {
-                        for y in y1..y2 {
-                            let by = y as f32 / ((y2 - y1) as f32);
-                            renderer.set(x1, y, color_a.lerp(color_b, by), blend_mode);
-                        }
-                    }
-                }
-            }
-
-            let mut d_x = x2 - x1;
-            let mut d_y = y2 - y1;
-            let mut i = 1;
-            if HIGH {
-                if d_x < 0 {
-                    i = -1;
-                    d_x = -d_x;
-                }
-            } else {
-                if d_x < 0 {
-                    i = -1;
-                    d_y = -d_y;
-                }
-            }
-            let mut d = (if HIGH {
-                (2 * d_x) - d_y
-            } else {
-                (2 * d_y) - d_x
-            }) as i32;
-            let mut x_or_y = if HIGH { x1 } else { y1 };
-            if HIGH {
-                match color {
-                    ColorMode::Solid(color) => {
-                        for y in y1..y2 {
-                            renderer.set(x_or_y, y, color, blend_mode);
-                            if d > 0 {
-                                x_or_y += i;
-                                d += (2 * (d_x - d_y)) as i32;
-                            } else {
-                                d += (2 * d_x) as i32;
-                            }
-                        }
-                    }
-                    ColorMode::PerPoint([color_a, color_b]) => {
-                        for y in y1..y2 {
-                            let by = y as f32 / ((y2 - y1) as f32);
-                            renderer.set(x_or_y, y, color_a.lerp(color_b, by), blend_mode);
-                            if d > 0 {
-                                x_or_y += i;
-                                d += (2 * (d_x - d_y)) as i32;
-                            } else {
-                                d += (2 * d_x) as i32;
-                            }
-                        }
-                    }
-                }
-            } else {
-                match color {
-                    ColorMode::Solid(color) => {
-                        for x in x1..x2 {
-                            renderer.set(x, x_or_y, color, blend_mode);
-                            if d > 0 {
-                                x_or_y += i;
-                                d += (2 * (d_y - d_x)) as i32;
-                            } else {
-                                d += (2 * d_y) as i32;
-                            }
-                        }
-                    }
-                    ColorMode::PerPoint([color_a, color_b]) => {
-                        for x in x1..x2 {
-                            let by = x as f32 / ((x2 - x1) as f32);
-                            renderer.set(x, x_or_y, color_a.lerp(color_b, by), blend_mode);
-                            if d > 0 {
-                                x_or_y += i;
-                                d += (2 * (d_y - d_x)) as i32;
-                            } else {
-                                d += (2 * d_y) as i32;
-                            }
-                        }
-                    }
-                }
-            }
+
+fn plot_line_thick<P: PixelFormat, S: Scalar, B: Blender>(
+    renderer: &mut Renderer<P, S>,
+    a: Vec2,
+    b: Vec2,
+    width: u16,
+    cap: LineCap,
+    color: ColorMode<2>,
+    blend_mode: &B,
+) {
+    // stroke between the centers of the end pixels
+    let a = [S::from_f32(a.x) + S::HALF, S::from_f32(a.y) + S::HALF];
+    let b = [S::from_f32(b.x) + S::HALF, S::from_f32(b.y) + S::HALF];
+    let mut pieces = Vec::new();
+    stroke_polyline(
+        &[a, b],
+        false,
+        S::from_ratio(width as i32, 2),
+        cap,
+        LineJoin::Bevel,
+        &mut pieces,
+    );
+
+    match (color, direction(a, b)) {
+        (ColorMode::PerPoint([color_a, color_b]), Some(dir)) => {
+            // project pixel centers onto the line for the gradient parameter
+            let len = (b[0] - a[0]) * dir[0] + (b[1] - a[1]) * dir[1];
+            fill_pieces(renderer, &pieces, blend_mode, |x, y| {
+                let by = ((x - a[0]) * dir[0] + (y - a[1]) * dir[1]) / len;
+                let by = if by < S::ZERO {
+                    S::ZERO
+                } else if by > S::ONE {
+                    S::ONE
+                } else {
+                    by
+                };
+                color_a.lerp(color_b, by)
+            });
         }
+        (ColorMode::Solid(color) | ColorMode::PerPoint([color, _]), _) => {
+            fill_pieces(renderer, &pieces, blend_mode, |_, _| color);
+        }
+    }
+}
+
+/// A line walked one pixel at a time along its major axis, in subpixels.
+struct LineSteps {
+    steep: bool,
+    // (major, minor) positions, with `start` on the lower major side
+    start: [i64; 2],
+    delta: [i64; 2],
+    colors: [FBColor; 2],
+}
+impl LineSteps {
+    fn new(a: Vec2, b: Vec2, color: ColorMode<2>) -> Self {
+        let a = [to_subpixel(a.x), to_subpixel(a.y)];
+        let b = [to_subpixel(b.x), to_subpixel(b.y)];
+        let steep = (b[1] - a[1]).abs() > (b[0] - a[0]).abs();
+        let (mut start, mut end) = if steep {
+            ([a[1], a[0]], [b[1], b[0]])
+        } else {
+            (a, b)
+        };
+        let mut colors = match color {
+            ColorMode::Solid(color) => [color; 2],
+            ColorMode::PerPoint(colors) => colors,
+        };
+        if start[0] > end[0] {
+            core::mem::swap(&mut start, &mut end);
+            colors.swap(0, 1);
+        }
+        Self {
+            steep,
+            start,
+            delta: [(end[0] - start[0]).max(1), end[1] - start[1]],
+            colors,
+        }
+    }
+
+    // pixels along the major axis with centers from `start` to the end, clipped
+    fn major_range<P: PixelFormat, S: Scalar>(
+        &self,
+        renderer: &Renderer<P, S>,
+        include_end: bool,
+    ) -> Range<i64> {
+        let (clip_start, clip_end) = if self.steep {
+            (renderer.clip_min().y, renderer.clip_max().y)
+        } else {
+            (renderer.clip_min().x, renderer.clip_max().x)
+        };
+        let end = self.start[0] + self.delta[0];
+        let first = -(-self.start[0]).div_euclid(SUBPIXEL);
+        let last = if include_end {
+            end.div_euclid(SUBPIXEL) + 1
+        } else {
+            -(-end).div_euclid(SUBPIXEL)
+        };
+        first.max(clip_start as i64)..last.min(clip_end as i64)
+    }
 
-        if (self.a.x < 0 && self.b.x < 0)
-            || (self.a.y < 0 && self.b.y < 0)
-            || (self.a.x < renderer.width() && self.b.x < renderer.width())
-            || (self.a.y < renderer.height() && self.b.y < renderer.height())
-        {
+    // pixels along the minor axis inside the clip rect. steps outside of it are
+    // skipped before their position is narrowed to an i16
+    fn minor_range<P: PixelFormat, S: Scalar>(&self, renderer: &Renderer<P, S>) -> Range<i64> {
+        let (clip_start, clip_end) = if self.steep {
+            (renderer.clip_min().x, renderer.clip_max().x)
+        } else {
+            (renderer.clip_min().y, renderer.clip_max().y)
+        };
+        clip_start as i64..clip_end as i64
+    }
+
+    // `delta[1] / delta[0] * (major - start[0]) + start[1] + offset` at the center of
+    // `major`, in pixels, split into a whole pixel and the remainder over `denominator`
+    fn minor_at(&self, major: i64, offset: i64) -> (i64, i64) {
+        let numerator = (major * SUBPIXEL - self.start[0]) * self.delta[1]
+            + (self.start[1] + offset) * self.delta[0];
+        let denominator = self.denominator();
+        (
+            numerator.div_euclid(denominator),
+            numerator.rem_euclid(denominator),
+        )
+    }
+
+    fn denominator(&self) -> i64 {
+        self.delta[0] * SUBPIXEL
+    }
+
+    // change of the remainder from one major pixel to the next, less than one pixel
+    fn step(&self) -> i64 {
+        self.delta[1] * SUBPIXEL
+    }
+
+    fn color<S: Scalar>(&self, major: i64) -> FBColor {
+        let by = S::from_ratio(
+            (major * SUBPIXEL - self.start[0]) as i32,
+            self.delta[0] as i32,
+        );
+        let by = if by < S::ZERO {
+            S::ZERO
+        } else if by > S::ONE {
+            S::ONE
+        } else {
+            by
+        };
+        self.colors[0].lerp(self.colors[1], by)
+    }
+
+    fn point(&self, major: i64, minor: i64) -> (i16, i16) {
+        if self.steep {
+            (minor as i16, major as i16)
+        } else {
+            (major as i16, minor as i16)
+        }
+    }
+}
+
+// walks the pixel nearest to the line at every step, leaving out the last pixel
+fn walk_line(
+    steps: &LineSteps,
+    range: Range<i64>,
+    minors: Range<i64>,
+    mut plot: impl FnMut(i64, i64),
+) {
+    let (denominator, step) = (steps.denominator(), steps.step());
+    // rounds to the nearest pixel, like a bresenham line
+    let (mut minor, mut rem) = steps.minor_at(range.start, SUBPIXEL / 2);
+
+    for major in range {
+        if minors.contains(&minor) {
+            plot(major, minor);
+        }
+
+        rem += step;
+        if rem >= denominator {
+            minor += 1;
+            rem -= denominator;
+        } else if rem < 0 {
+            minor -= 1;
+            rem += denominator;
+        }
+    }
+}
+
+fn plot_line<P: PixelFormat, S: Scalar, B: Blender>(
+    renderer: &mut Renderer<P, S>,
+    a: Vec2,
+    b: Vec2,
+    color: ColorMode<2>,
+    blend_mode: &B,
+) {
+    let steps = LineSteps::new(a, b, color);
+    let range = steps.major_range(renderer, false);
+    let minors = steps.minor_range(renderer);
+    walk_line(&steps, range, minors, |major, minor| {
+        let color = match color {
+            ColorMode::Solid(color) => color,
+            ColorMode::PerPoint(_) => steps.color::<S>(major),
+        };
+        let (x, y) = steps.point(major, minor);
+        renderer.set(x, y, color, blend_mode);
+    });
+}
+
+/// Appends the pixels a one pixel wide, aliased [`Line`] from `a` to `b` covers inside
+/// the clip rect, for shapes that blend overlapping lines once.
+pub(crate) fn line_pixels<P: PixelFormat, S: Scalar>(
+    renderer: &Renderer<P, S>,
+    a: Vec2,
+    b: Vec2,
+    out: &mut Vec<(i16, i16)>,
+) {
+    // only the positions are used, so the color doesn't matter
+    let steps = LineSteps::new(a, b, ColorMode::Solid(FBColor::WHITE));
+    let range = steps.major_range(renderer, false);
+    let minors = steps.minor_range(renderer);
+    walk_line(&steps, range, minors, |major, minor| {
+        out.push(steps.point(major, minor))
+    });
+}
+
+fn plot_line_aa<P: PixelFormat, S: Scalar, B: Blender>(
+    renderer: &mut Renderer<P, S>,
+    a: Vec2,
+    b: Vec2,
+    color: ColorMode<2>,
+    blend_mode: &B,
+) {
+    let steps = LineSteps::new(a, b, color);
+    let range = steps.major_range(renderer, true);
+    let minors = steps.minor_range(renderer);
+    let (denominator, step) = (steps.denominator(), steps.step());
+    // exact minor position, split into a whole pixel and a fraction towards the next
+    let (mut minor, mut rem) = steps.minor_at(range.start, 0);
+
+    let mut plot = |major: i64, minor: i64, color: FBColor, coverage: S| {
+        if !minors.contains(&minor) {
             return;
         }
+        let color = color.with_a(color.a() * coverage.to_f32());
+        let (x, y) = steps.point(major, minor);
+        renderer.set(x, y, color, blend_mode);
+    };
+
+    for major in range {
+        let frac = S::from_ratio(rem as i32, denominator as i32);
+        let color = steps.color::<S>(major);
+
+        plot(major, minor, color, S::ONE - frac);
+        if frac > S::ZERO {
+            plot(major, minor + 1, color, frac);
+        }
 
-        if (self.a.y - self.b.y).abs() < (self.a.x - self.b.x).abs() {
-            if self.a.x > self.b.x {
-                plot_line_common::<false>(
-                    renderer,
-                    self.b.x,
-                    self.b.y,
-                    self.a.x,
-                    self.a.y,
-                    self.color,
-                    self.blend_mode,
-                );
-            } else {
-                plot_line_common::<false>(
-                    renderer,
-                    self.a.x,
-                    self.a.y,
-                    self.b.x,
-                    self.b.y,
-                    self.color,
-                    self.blend_mode,
-                );
-            }
+        rem += step;
+        if rem >= denominator {
+            minor += 1;
+            rem -= denominator;
+        } else if rem < 0 {
+            minor -= 1;
+            rem += denominator;
+        }
+    }
+}
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for Line<B> {
+    fn draw(&self, renderer: &mut crate::Renderer<P, S>) {
+        let color = self.color.map(|c| renderer.decode_color(c));
+        if self.width > 1 {
+            plot_line_thick(
+                renderer,
+                self.a,
+                self.b,
+                self.width,
+                self.cap,
+                color,
+                &self.blend_mode,
+            );
+        } else if self.anti_aliased {
+            plot_line_aa(renderer, self.a, self.b, color, &self.blend_mode);
         } else {
-            if self.a.y > self.b.y {
-                plot_line_common::<true>(
-                    renderer,
-                    self.b.x,
-                    self.b.y,
-                    self.a.x,
-                    self.a.y,
-                    self.color,
-                    self.blend_mode,
-                );
-            } else {
-                plot_line_common::<true>(
-                    renderer,
-                    self.a.x,
-                    self.a.y,
-                    self.b.x,
-                    self.b.y,
-                    self.color,
-                    self.blend_mode,
-                );
-            }
+            plot_line(renderer, self.a, self.b, color, &self.blend_mode);
         }
     }
 }
@@ -197,10 +326,9 @@ mod tests {
 
     use ::test::Bencher;
     use core::ffi::*;
-    use glam::I16Vec2;
     use std::random::Random;
 
-    use crate::{BlendMode, Line, Renderer, ffi::*};
+    use crate::{BlendMode, Line, Renderer, Vec2, ffi::*};
 
     #[bench]
     fn bmp_line(bencher: &mut Bencher) {
@@ -225,16 +353,36 @@ mod tests {
         let mut renderer = test::black_box(Renderer::new(128, 128));
         let mut rand = std::random::DefaultRandomSource;
         let mut line = Line::new(
-            I16Vec2::ZERO,
-            I16Vec2::ZERO,
+            Vec2::ZERO,
+            Vec2::ZERO,
             crate::ColorMode::Solid(FBColor::WHITE),
             BlendMode::Opaque,
         );
         bencher.iter(|| {
-            line.a.x = c_short::random(&mut rand);
-            line.a.y = c_short::random(&mut rand);
-            line.b.x = c_short::random(&mut rand);
-            line.b.y = c_short::random(&mut rand);
+            line.a.x = c_short::random(&mut rand) as f32;
+            line.a.y = c_short::random(&mut rand) as f32;
+            line.b.x = c_short::random(&mut rand) as f32;
+            line.b.y = c_short::random(&mut rand) as f32;
+            line.draw(&mut renderer)
+        })
+    }
+
+    #[bench]
+    fn our_line_aa(bencher: &mut Bencher) {
+        let mut renderer = test::black_box(Renderer::new(128, 128));
+        let mut rand = std::random::DefaultRandomSource;
+        let mut line = Line::new(
+            Vec2::ZERO,
+            Vec2::ZERO,
+            crate::ColorMode::Solid(FBColor::WHITE),
+            BlendMode::Alpha,
+        );
+        line.anti_aliased = true;
+        bencher.iter(|| {
+            line.a.x = c_short::random(&mut rand) as f32;
+            line.a.y = c_short::random(&mut rand) as f32;
+            line.b.x = c_short::random(&mut rand) as f32;
+            line.b.y = c_short::random(&mut rand) as f32;
             line.draw(&mut renderer)
         })
     }
@@ -0,0 +1,127 @@
+use alloc::vec::Vec;
+
+use crate::texture::{Texture, TextureFilter};
+use crate::triangle::{rasterize_subpixel, subpixel_point};
+use crate::{BlendMode, Blender, Drawable, FBColor, PixelFormat, Renderer, Scalar, Vec2};
+
+#[derive(Clone, Copy)]
+pub struct MeshVertex {
+    /// Positioned like [`Triangle`](crate::Triangle) vertices.
+    pub position: Vec2,
+    pub color: FBColor,
+    /// Texture coordinates, unused by meshes without a texture.
+    pub uv: Vec2,
+}
+impl MeshVertex {
+    pub const fn new(position: Vec2, color: FBColor, uv: Vec2) -> Self {
+        Self {
+            position,
+            color,
+            uv,
+        }
+    }
+}
+
+/// Triangles sharing vertices, drawn without overlapping along their shared edges.
+pub struct Mesh<B = BlendMode> {
+    pub vertices: Vec<MeshVertex>,
+    /// Every three indices into `vertices` make up a triangle. Triangles with an index
+    /// past the end of `vertices` are skipped.
+    pub indices: Vec<u16>,
+    /// Multiplied with the vertex colors, when set.
+    pub texture: Option<Texture>,
+    pub filter: TextureFilter,
+    pub blend_mode: B,
+}
+impl<B: Blender> Mesh<B> {
+    pub fn new(vertices: Vec<MeshVertex>, indices: Vec<u16>, blend_mode: B) -> Self {
+        Self {
+            vertices,
+            indices,
+            texture: None,
+            filter: TextureFilter::Nearest,
+            blend_mode,
+        }
+    }
+}
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for Mesh<B> {
+    fn draw(&self, renderer: &mut Renderer<P, S>) {
+        let clip_min = renderer.clip_min();
+        let clip_max = renderer.clip_max();
+
+        // faces share vertices, so their setup is done once for the whole mesh
+        let vertices: Vec<_> = self
+            .vertices
+            .iter()
+            .map(|v| {
+                (
+                    subpixel_point(v.position),
+                    renderer.decode_color(v.color),
+                    [S::from_f32(v.uv.x), S::from_f32(v.uv.y)],
+                )
+            })
+            .collect();
+
+        for face in self.indices.chunks_exact(3) {
+            let (Some(a), Some(b), Some(c)) = (
+                vertices.get(face[0] as usize),
+                vertices.get(face[1] as usize),
+                vertices.get(face[2] as usize),
+            ) else {
+                continue;
+            };
+            let colors = [a.1, b.1, c.1];
+            let uv = [a.2, b.2, c.2];
+
+            rasterize_subpixel(a.0, b.0, c.0, clip_min, clip_max, |x, y, weights, area| {
+                let weights = weights.map(|w| S::from_ratio(w, area));
+                let color = FBColor::lerp3(&colors, &weights);
+                let color = match &self.texture {
+                    Some(texture) => {
+                        let u =
+                            weights[0] * uv[0][0] + weights[1] * uv[1][0] + weights[2] * uv[2][0];
+                        let v =
+                            weights[0] * uv[0][1] + weights[1] * uv[1][1] + weights[2] * uv[2][1];
+                        texture.sample(u, v, self.filter, |c| renderer.decode_color(c)) * color
+                    }
+                    None => color,
+                };
+                renderer.set_unchecked(x, y, color, &self.blend_mode);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Drawable, FBColor};
+    extern crate std;
+
+    use ::test::Bencher;
+    use alloc::vec;
+    use core::ffi::*;
+    use std::random::Random;
+
+    use crate::{BlendMode, Renderer, Vec2};
+
+    use super::{Mesh, MeshVertex};
+
+    #[bench]
+    fn our_mesh(bencher: &mut Bencher) {
+        let mut renderer = test::black_box(Renderer::new(128, 128));
+        let mut rand = std::random::DefaultRandomSource;
+        // a fan of triangles around the first vertex
+        let mut mesh = Mesh::new(
+            vec![MeshVertex::new(Vec2::ZERO, FBColor::MAGENTA, Vec2::ZERO); 9],
+            (1..8).flat_map(|i| [0, i, i + 1]).collect(),
+            BlendMode::Opaque,
+        );
+        bencher.iter(|| {
+            for vertex in &mut mesh.vertices {
+                vertex.position.x = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+                vertex.position.y = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            }
+            mesh.draw(&mut renderer);
+        })
+    }
+}
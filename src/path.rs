@@ -0,0 +1,498 @@
+use alloc::vec::Vec;
+use glam::Vec2;
+
+use crate::polygon::fill_polygon;
+use crate::scalar::ceil_i32;
+use crate::stroke::{Point, abs, add, fill_pieces, scale, stroke_polyline, sub};
+use crate::{
+    BlendMode, Blender, Drawable, FBColor, FillRule, LineCap, LineJoin, PixelFormat, Renderer,
+    Scalar,
+};
+
+// most a flattened curve strays from the real one, in pixels
+const TOLERANCE: (i32, i32) = (1, 4);
+const MAX_CURVE_SEGMENTS: i32 = 256;
+const MAX_ARC_DEPTH: u32 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PathSegment {
+    MoveTo(Vec2),
+    LineTo(Vec2),
+    QuadTo(Vec2, Vec2),
+    CubicTo(Vec2, Vec2, Vec2),
+    ArcTo {
+        radii: Vec2,
+        x_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Vec2,
+    },
+    Close,
+}
+
+/// Outline made of lines and curves. Points are in pixels, with whole numbers at pixel
+/// centers like the points of [`Polygon`](crate::Polygon) and [`Polyline`](crate::Polyline).
+///
+/// Curves are flattened into lines when drawn, using [`FilledPath`] or [`StrokedPath`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+pub(crate) struct Subpath<S> {
+    pub(crate) points: Vec<Point<S>>,
+    pub(crate) closed: bool,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Starts a new subpath at `to`.
+    pub fn move_to(&mut self, to: Vec2) -> &mut Self {
+        self.segments.push(PathSegment::MoveTo(to));
+        self
+    }
+
+    pub fn line_to(&mut self, to: Vec2) -> &mut Self {
+        self.segments.push(PathSegment::LineTo(to));
+        self
+    }
+
+    pub fn quad_to(&mut self, control: Vec2, to: Vec2) -> &mut Self {
+        self.segments.push(PathSegment::QuadTo(control, to));
+        self
+    }
+
+    pub fn cubic_to(&mut self, control_1: Vec2, control_2: Vec2, to: Vec2) -> &mut Self {
+        self.segments
+            .push(PathSegment::CubicTo(control_1, control_2, to));
+        self
+    }
+
+    /// Elliptical arc to `to`, parameterized like the svg `A` command.
+    ///
+    /// `x_rotation` is in radians. Radii too small to reach `to` are scaled up,
+    /// and a zero radius makes the arc a straight line.
+    pub fn arc_to(
+        &mut self,
+        radii: Vec2,
+        x_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Vec2,
+    ) -> &mut Self {
+        self.segments.push(PathSegment::ArcTo {
+            radii,
+            x_rotation,
+            large_arc,
+            sweep,
+            to,
+        });
+        self
+    }
+
+    /// Closes the current subpath with a line back to its start.
+    pub fn close(&mut self) -> &mut Self {
+        self.segments.push(PathSegment::Close);
+        self
+    }
+
+    pub(crate) fn flatten<S: Scalar>(&self) -> Vec<Subpath<S>> {
+        // moved to the pixel centers the fill samples at
+        let point = |p: Vec2| [S::from_f32(p.x) + S::HALF, S::from_f32(p.y) + S::HALF];
+
+        let mut subpaths = Vec::new();
+        let mut points: Vec<Point<S>> = Vec::new();
+        let mut start = [S::ZERO; 2];
+        let mut last = [S::ZERO; 2];
+
+        let mut finish = |points: &mut Vec<Point<S>>, closed: bool| {
+            if points.len() > 1 {
+                subpaths.push(Subpath {
+                    points: core::mem::take(points),
+                    closed,
+                });
+            }
+            points.clear();
+        };
+
+        for segment in &self.segments {
+            // drawing after a close continues from where the closed subpath started
+            if points.is_empty() {
+                points.push(last);
+            }
+            match *segment {
+                PathSegment::MoveTo(to) => {
+                    finish(&mut points, false);
+                    last = point(to);
+                    start = last;
+                    points.push(last);
+                    continue;
+                }
+                PathSegment::LineTo(to) => points.push(point(to)),
+                PathSegment::QuadTo(control, to) => {
+                    flatten_quad(last, point(control), point(to), &mut points);
+                }
+                PathSegment::CubicTo(control_1, control_2, to) => flatten_cubic(
+                    last,
+                    point(control_1),
+                    point(control_2),
+                    point(to),
+                    &mut points,
+                ),
+                PathSegment::ArcTo {
+                    radii,
+                    x_rotation,
+                    large_arc,
+                    sweep,
+                    to,
+                } => flatten_arc(
+                    last,
+                    [S::from_f32(radii.x.abs()), S::from_f32(radii.y.abs())],
+                    S::from_f32(x_rotation),
+                    large_arc,
+                    sweep,
+                    point(to),
+                    &mut points,
+                ),
+                PathSegment::Close => {
+                    finish(&mut points, true);
+                    last = start;
+                    continue;
+                }
+            }
+            last = *points.last().unwrap();
+        }
+        finish(&mut points, false);
+        subpaths
+    }
+}
+
+fn tolerance<S: Scalar>() -> S {
+    S::from_ratio(TOLERANCE.0, TOLERANCE.1)
+}
+
+// number of segments that keep a curve within the tolerance, given the largest second
+// difference of its control points. chords of `n` segments stray up to
+// `factor * second difference / n²`, where `factor` is 1 / 4 for quads and 3 / 4 for cubics.
+fn curve_segments<S: Scalar>(second_difference: Point<S>, factor: S) -> i32 {
+    // manhattan length overestimates a little, but can't overflow like a square would
+    let length = abs(second_difference[0]) + abs(second_difference[1]);
+    ceil_i32((length * factor / tolerance::<S>()).sqrt()).clamp(1, MAX_CURVE_SEGMENTS)
+}
+
+fn flatten_quad<S: Scalar>(p0: Point<S>, p1: Point<S>, p2: Point<S>, out: &mut Vec<Point<S>>) {
+    let second_difference = add(sub(p0, scale(p1, S::from_i32(2))), p2);
+    let n = curve_segments(second_difference, S::from_ratio(1, 4));
+    for i in 1..=n {
+        let t = S::from_ratio(i, n);
+        let mt = S::ONE - t;
+        out.push(add(
+            add(scale(p0, mt * mt), scale(p1, S::from_i32(2) * mt * t)),
+            scale(p2, t * t),
+        ));
+    }
+}
+
+fn flatten_cubic<S: Scalar>(
+    p0: Point<S>,
+    p1: Point<S>,
+    p2: Point<S>,
+    p3: Point<S>,
+    out: &mut Vec<Point<S>>,
+) {
+    let two = S::from_i32(2);
+    let three = S::from_i32(3);
+    let dd0 = add(sub(p0, scale(p1, two)), p2);
+    let dd1 = add(sub(p1, scale(p2, two)), p3);
+    let second_difference = if abs(dd0[0]) + abs(dd0[1]) > abs(dd1[0]) + abs(dd1[1]) {
+        dd0
+    } else {
+        dd1
+    };
+    let n = curve_segments(second_difference, S::from_ratio(3, 4));
+    for i in 1..=n {
+        let t = S::from_ratio(i, n);
+        let mt = S::ONE - t;
+        out.push(add(
+            add(scale(p0, mt * mt * mt), scale(p1, three * mt * mt * t)),
+            add(scale(p2, three * mt * t * t), scale(p3, t * t * t)),
+        ));
+    }
+}
+
+fn normalize<S: Scalar>(v: Point<S>) -> Point<S> {
+    let len = (v[0] * v[0] + v[1] * v[1]).sqrt();
+    [v[0] / len, v[1] / len]
+}
+
+// converts the endpoints to a center and unit circle directions, as in the svg
+// implementation notes, then splits the arc by repeatedly bisecting those directions
+fn flatten_arc<S: Scalar>(
+    from: Point<S>,
+    radii: Point<S>,
+    x_rotation: S,
+    large_arc: bool,
+    sweep: bool,
+    to: Point<S>,
+    out: &mut Vec<Point<S>>,
+) {
+    let (mut rx, mut ry) = (radii[0], radii[1]);
+    if from == to {
+        return;
+    }
+    if rx <= S::ZERO || ry <= S::ZERO {
+        out.push(to);
+        return;
+    }
+
+    let (sin, cos) = x_rotation.sin_cos();
+    let rotate = |v: Point<S>| [v[0] * cos - v[1] * sin, v[0] * sin + v[1] * cos];
+    let half = scale(sub(from, to), S::HALF);
+    let half = [half[0] * cos + half[1] * sin, half[1] * cos - half[0] * sin];
+
+    // work relative to the radii, which keeps values small for narrow scalars
+    let mut a = half[0] / rx;
+    let mut b = half[1] / ry;
+    let lambda = a * a + b * b;
+    let mut coef = S::ZERO;
+    if lambda > S::ONE {
+        let grow = lambda.sqrt();
+        rx = rx * grow;
+        ry = ry * grow;
+        a = a / grow;
+        b = b / grow;
+    } else {
+        coef = ((S::ONE - lambda) / lambda).sqrt();
+        if large_arc == sweep {
+            coef = -coef;
+        }
+    }
+    let center = add(
+        rotate([coef * rx * b, -coef * ry * a]),
+        scale(add(from, to), S::HALF),
+    );
+    let start = [a - coef * b, b + coef * a];
+    let end = [-a - coef * b, -b + coef * a];
+
+    let to_screen = |u: Point<S>| add(center, rotate([u[0] * rx, u[1] * ry]));
+
+    // halfway along the arc from `u` to `v` in the sweep direction
+    let midpoint = |u: Point<S>, v: Point<S>| {
+        let cross = u[0] * v[1] - u[1] * v[0];
+        let sum = add(u, v);
+        if abs(sum[0]) + abs(sum[1]) <= S::from_ratio(1, 1024) {
+            // half a turn apart, so step a quarter turn from u
+            if sweep { [-u[1], u[0]] } else { [u[1], -u[0]] }
+        } else if (cross > S::ZERO) == sweep {
+            normalize(sum)
+        } else {
+            normalize([-sum[0], -sum[1]])
+        }
+    };
+
+    // after two bisections every part spans at most a quarter turn. each further
+    // bisection quarters the sagitta, which starts below r * (1 - cos(tau / 8)) ≈ r / 3
+    let radius = if rx > ry { rx } else { ry };
+    let mut sagitta = radius / S::from_i32(3);
+    let mut depth = 2;
+    while sagitta > tolerance::<S>() && depth < MAX_ARC_DEPTH {
+        sagitta = sagitta / S::from_i32(4);
+        depth += 1;
+    }
+
+    fn bisect<S: Scalar>(
+        u: Point<S>,
+        v: Point<S>,
+        depth: u32,
+        midpoint: &impl Fn(Point<S>, Point<S>) -> Point<S>,
+        to_screen: &impl Fn(Point<S>) -> Point<S>,
+        out: &mut Vec<Point<S>>,
+    ) {
+        if depth == 0 {
+            out.push(to_screen(v));
+            return;
+        }
+        let m = midpoint(u, v);
+        bisect(u, m, depth - 1, midpoint, to_screen, out);
+        bisect(m, v, depth - 1, midpoint, to_screen, out);
+    }
+    bisect(start, end, depth, &midpoint, &to_screen, out);
+    // land exactly on the endpoint
+    if let Some(last) = out.last_mut() {
+        *last = to;
+    }
+}
+
+pub struct FilledPath<B = BlendMode> {
+    pub path: Path,
+    pub fill_rule: FillRule,
+    pub color: FBColor,
+    pub blend_mode: B,
+}
+impl<B: Blender> FilledPath<B> {
+    pub fn new(path: Path, fill_rule: FillRule, color: FBColor, blend_mode: B) -> Self {
+        Self {
+            path,
+            fill_rule,
+            color,
+            blend_mode,
+        }
+    }
+}
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for FilledPath<B> {
+    fn draw(&self, renderer: &mut Renderer<P, S>) {
+        let color = renderer.decode_color(self.color);
+        let subpaths = self.path.flatten::<S>();
+        let contours = subpaths
+            .iter()
+            .map(|subpath| subpath.points.iter().copied());
+        fill_polygon(
+            renderer,
+            contours,
+            self.fill_rule,
+            &self.blend_mode,
+            |_, _| color,
+        );
+    }
+}
+
+pub struct StrokedPath<B = BlendMode> {
+    pub path: Path,
+    pub width: u16,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub color: FBColor,
+    pub blend_mode: B,
+}
+impl<B: Blender> StrokedPath<B> {
+    pub fn new(
+        path: Path,
+        width: u16,
+        cap: LineCap,
+        join: LineJoin,
+        color: FBColor,
+        blend_mode: B,
+    ) -> Self {
+        Self {
+            path,
+            width,
+            cap,
+            join,
+            color,
+            blend_mode,
+        }
+    }
+}
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for StrokedPath<B> {
+    fn draw(&self, renderer: &mut Renderer<P, S>) {
+        if self.width == 0 {
+            return;
+        }
+        let half_width = S::from_ratio(self.width as i32, 2);
+        let mut pieces = Vec::new();
+        for subpath in self.path.flatten::<S>() {
+            stroke_polyline(
+                &subpath.points,
+                subpath.closed,
+                half_width,
+                self.cap,
+                self.join,
+                &mut pieces,
+            );
+        }
+
+        let color = renderer.decode_color(self.color);
+        fill_pieces(renderer, &pieces, &self.blend_mode, |_, _| color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Drawable, FBColor};
+    extern crate std;
+
+    use ::test::Bencher;
+    use glam::Vec2;
+    use std::random::Random;
+
+    use crate::{BlendMode, FillRule, LineCap, LineJoin, Renderer};
+
+    use super::{FilledPath, Path, StrokedPath, flatten_quad, tolerance};
+
+    fn random_point(rand: &mut std::random::DefaultRandomSource) -> Vec2 {
+        Vec2::new(
+            (i16::random(rand) % 160) as f32 - 16.0,
+            (i16::random(rand) % 160) as f32 - 16.0,
+        )
+    }
+
+    #[bench]
+    fn our_filled_path(bencher: &mut Bencher) {
+        let mut renderer = test::black_box(Renderer::new(128, 128));
+        let mut rand = std::random::DefaultRandomSource;
+        bencher.iter(|| {
+            let mut path = Path::new();
+            path.move_to(random_point(&mut rand))
+                .quad_to(random_point(&mut rand), random_point(&mut rand))
+                .cubic_to(
+                    random_point(&mut rand),
+                    random_point(&mut rand),
+                    random_point(&mut rand),
+                )
+                .close();
+            FilledPath::new(path, FillRule::NonZero, FBColor::WHITE, BlendMode::Opaque)
+                .draw(&mut renderer);
+        })
+    }
+
+    #[bench]
+    fn our_stroked_path(bencher: &mut Bencher) {
+        let mut renderer = test::black_box(Renderer::new(128, 128));
+        let mut rand = std::random::DefaultRandomSource;
+        bencher.iter(|| {
+            let mut path = Path::new();
+            path.move_to(random_point(&mut rand))
+                .quad_to(random_point(&mut rand), random_point(&mut rand))
+                .arc_to(Vec2::splat(24.0), 0.0, false, true, random_point(&mut rand));
+            StrokedPath::new(
+                path,
+                3,
+                LineCap::Round,
+                LineJoin::Round,
+                FBColor::WHITE,
+                BlendMode::Opaque,
+            )
+            .draw(&mut renderer);
+        })
+    }
+
+    #[test]
+    fn quads_flatten_within_tolerance() {
+        let [p0, p1, p2] = [[0.0f32, 0.0], [50.0, 100.0], [100.0, 0.0]];
+        let mut points = alloc::vec![p0];
+        flatten_quad(p0, p1, p2, &mut points);
+        let n = (points.len() - 1) as f32;
+
+        let quad = |t: f32| {
+            let mt = 1.0 - t;
+            [0, 1].map(|i| mt * mt * p0[i] + 2.0 * mt * t * p1[i] + t * t * p2[i])
+        };
+        // chords stray furthest from a quad halfway between their ends
+        for (i, chord) in points.windows(2).enumerate() {
+            let curve = quad((i as f32 + 0.5) / n);
+            let [dx, dy] = [0, 1].map(|j| (chord[0][j] + chord[1][j]) / 2.0 - curve[j]);
+            let tolerance = tolerance::<f32>();
+            assert!(
+                dx * dx + dy * dy <= tolerance * tolerance * 1.001,
+                "chord {i} strays ({dx}, {dy}) from the curve"
+            );
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use crate::FBColor;
+use crate::blend::{blend_alpha, blend_none};
+
+/// Storage format of a framebuffer pixel.
+///
+/// Pixels are stored with premultiplied alpha, like [`FBColor`] framebuffers.
+///
+/// Packed formats blend natively only for [`BlendMode::Opaque`](crate::BlendMode::Opaque)
+/// and [`BlendMode::Alpha`](crate::BlendMode::Alpha). Every other blend mode goes through
+/// [`PixelFormat::blend_with`], which unpacks the pixel to an [`FBColor`] and packs it
+/// again for every pixel drawn.
+pub trait PixelFormat: Copy + 'static {
+    const EMPTY: Self;
+
+    fn from_fbcolor(color: FBColor) -> Self;
+
+    fn to_fbcolor(self) -> FBColor;
+
+    fn blend_none(src: FBColor, dst: &mut Self) {
+        *dst = Self::from_fbcolor(src.premultiplied());
+    }
+
+    fn blend_alpha(src: FBColor, dst: &mut Self) {
+        Self::blend_with(src, dst, blend_alpha);
+    }
+
+    fn blend_with(src: FBColor, dst: &mut Self, blend: impl FnOnce(FBColor, &mut FBColor)) {
+        let mut dst_color = dst.to_fbcolor();
+        blend(src, &mut dst_color);
+        *dst = Self::from_fbcolor(dst_color);
+    }
+}
+
+impl PixelFormat for FBColor {
+    const EMPTY: Self = FBColor::EMPTY;
+
+    fn from_fbcolor(color: FBColor) -> Self {
+        color
+    }
+
+    fn to_fbcolor(self) -> FBColor {
+        self
+    }
+
+    fn blend_none(src: FBColor, dst: &mut Self) {
+        blend_none(src, dst);
+    }
+
+    fn blend_alpha(src: FBColor, dst: &mut Self) {
+        blend_alpha(src, dst);
+    }
+
+    fn blend_with(src: FBColor, dst: &mut Self, blend: impl FnOnce(FBColor, &mut FBColor)) {
+        blend(src, dst);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct Rgba8(pub [u8; 4]);
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct Bgra8(pub [u8; 4]);
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct Rgb565(pub u16);
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct Argb1555(pub u16);
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct L8(pub u8);
+
+// exact rounding division by 255 for values in 0..=255*255
+const fn div255(x: u16) -> u8 {
+    let x = x as u32 + 128;
+    ((x + (x >> 8)) >> 8) as u8
+}
+
+const fn expand5(v: u16) -> u8 {
+    let v = (v & 0x1f) as u8;
+    (v << 3) | (v >> 2)
+}
+
+const fn expand6(v: u16) -> u8 {
+    let v = (v & 0x3f) as u8;
+    (v << 2) | (v >> 4)
+}
+
+impl Rgba8 {
+    const fn pack([r, g, b, a]: [u8; 4]) -> Self {
+        Self([r, g, b, a])
+    }
+
+    const fn unpack(self) -> [u8; 4] {
+        self.0
+    }
+}
+
+impl Bgra8 {
+    const fn pack([r, g, b, a]: [u8; 4]) -> Self {
+        Self([b, g, r, a])
+    }
+
+    const fn unpack(self) -> [u8; 4] {
+        let [b, g, r, a] = self.0;
+        [r, g, b, a]
+    }
+}
+
+impl Rgb565 {
+    const fn pack([r, g, b, _]: [u8; 4]) -> Self {
+        Self(((r as u16 >> 3) << 11) | ((g as u16 >> 2) << 5) | (b as u16 >> 3))
+    }
+
+    const fn unpack(self) -> [u8; 4] {
+        [
+            expand5(self.0 >> 11),
+            expand6(self.0 >> 5),
+            expand5(self.0),
+            0xff,
+        ]
+    }
+}
+
+// rounded inverse of premultiplying `c` by `a`
+const fn unpremultiply8(c: u8, a: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+    let c = (c as u16 * 255 + a as u16 / 2) / a as u16;
+    if c > 0xff { 0xff } else { c as u8 }
+}
+
+// alpha is rounded to one bit, which premultiplied color can't follow, so color is
+// stored straight and premultiplied again by the rounded alpha when read
+impl Argb1555 {
+    const fn pack([r, g, b, a]: [u8; 4]) -> Self {
+        let [r, g, b] = [
+            unpremultiply8(r, a) as u16,
+            unpremultiply8(g, a) as u16,
+            unpremultiply8(b, a) as u16,
+        ];
+        Self((((a >= 0x80) as u16) << 15) | ((r >> 3) << 10) | ((g >> 3) << 5) | (b >> 3))
+    }
+
+    const fn unpack(self) -> [u8; 4] {
+        if self.0 & 0x8000 == 0 {
+            return [0; 4];
+        }
+        [
+            expand5(self.0 >> 10),
+            expand5(self.0 >> 5),
+            expand5(self.0),
+            0xff,
+        ]
+    }
+}
+
+impl L8 {
+    // rec. 709 luma weights in 8-bit fixed point
+    const fn pack([r, g, b, _]: [u8; 4]) -> Self {
+        Self(((r as u32 * 54 + g as u32 * 183 + b as u32 * 19) >> 8) as u8)
+    }
+
+    const fn unpack(self) -> [u8; 4] {
+        [self.0, self.0, self.0, 0xff]
+    }
+}
+
+// integer version of `blend_alpha`, operating on unpacked 8-bit channels
+fn blend_alpha_rgba8(src: [u8; 4], dst: [u8; 4]) -> [u8; 4] {
+    let a = src[3] as u16;
+    let inv_a = 0xff - a;
+    let channel = |s: u8, d: u8| div255(s as u16 * a).saturating_add(div255(d as u16 * inv_a));
+    [
+        channel(src[0], dst[0]),
+        channel(src[1], dst[1]),
+        channel(src[2], dst[2]),
+        src[3].saturating_add(div255(dst[3] as u16 * inv_a)),
+    ]
+}
+
+macro_rules! impl_packed_format {
+    ($($format:ty),+) => {
+        $(
+            impl PixelFormat for $format {
+                const EMPTY: Self = Self::pack([0; 4]);
+
+                fn from_fbcolor(color: FBColor) -> Self {
+                    Self::pack(color.to_rgba8())
+                }
+
+                fn to_fbcolor(self) -> FBColor {
+                    let [r, g, b, a] = self.unpack();
+                    FBColor::from_rgba8(r, g, b, a)
+                }
+
+                fn blend_alpha(src: FBColor, dst: &mut Self) {
+                    let src = src.to_rgba8();
+                    match src[3] {
+                        0 => {}
+                        0xff => *dst = Self::pack(src),
+                        _ => *dst = Self::pack(blend_alpha_rgba8(src, dst.unpack())),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_packed_format!(Rgba8, Bgra8, Rgb565, Argb1555, L8);
+
+#[cfg(test)]
+mod tests {
+    use crate::FBColor;
+
+    use super::{Argb1555, Bgra8, L8, PixelFormat, Rgb565, Rgba8, expand5, expand6};
+
+    // stores a straight color the way the renderer does, and reads it back the way
+    // `Renderer::fb_rgba8` does
+    fn round_trip<P: PixelFormat>([r, g, b, a]: [u8; 4]) -> [u8; 4] {
+        P::from_fbcolor(FBColor::from_rgba8(r, g, b, a).premultiplied())
+            .to_fbcolor()
+            .unpremultiplied()
+            .to_rgba8()
+    }
+
+    fn assert_near(actual: [u8; 4], expected: [u8; 4], tolerance: u8) {
+        assert!(
+            actual
+                .iter()
+                .zip(&expected)
+                .all(|(a, e)| a.abs_diff(*e) <= tolerance),
+            "{actual:?} isn't within {tolerance} of {expected:?}"
+        );
+    }
+
+    #[test]
+    fn rgba8_and_bgra8_round_trip() {
+        for v in 0..=255 {
+            for color in [[v, 0, 255, 255], [255, v, 0, 255], [0, 255, v, 255]] {
+                assert_eq!(round_trip::<Rgba8>(color), color);
+                assert_eq!(round_trip::<Bgra8>(color), color);
+            }
+        }
+        // premultiplying rounds the color of translucent pixels
+        assert_near(
+            round_trip::<Rgba8>([200, 100, 50, 128]),
+            [200, 100, 50, 128],
+            1,
+        );
+        assert_near(
+            round_trip::<Bgra8>([200, 100, 50, 128]),
+            [200, 100, 50, 128],
+            1,
+        );
+
+        let color = FBColor::from_rgba8(10, 20, 30, 255);
+        assert_eq!(Rgba8::from_fbcolor(color).0, [10, 20, 30, 255]);
+        assert_eq!(Bgra8::from_fbcolor(color).0, [30, 20, 10, 255]);
+    }
+
+    #[test]
+    fn rgb565_round_trip() {
+        // values a channel holds exactly read back unchanged
+        for v in 0..32 {
+            let (c5, c6) = (expand5(v), expand6(v * 2));
+            assert_eq!(round_trip::<Rgb565>([c5, c6, c5, 255]), [c5, c6, c5, 255]);
+        }
+        // others read back within one step of their channel
+        for v in 0..=255 {
+            let [r, g, b, a] = round_trip::<Rgb565>([v, v, v, 255]);
+            assert!(r.abs_diff(v) < 8 && g.abs_diff(v) < 4 && b.abs_diff(v) < 8);
+            assert_eq!(a, 255);
+        }
+        // without alpha, translucent pixels read back as if drawn over black
+        assert_near(
+            round_trip::<Rgb565>([255, 255, 255, 128]),
+            [128, 128, 128, 255],
+            8,
+        );
+    }
+
+    #[test]
+    fn argb1555_round_trip() {
+        for v in 0..32 {
+            let c = expand5(v);
+            assert_eq!(round_trip::<Argb1555>([c, c, c, 255]), [c, c, c, 255]);
+        }
+        // alpha rounds to opaque from 0x80, keeping the color rather than darkening it
+        for a in [0x80, 0xc0, 0xfe] {
+            assert_near(
+                round_trip::<Argb1555>([200, 100, 50, a]),
+                [200, 100, 50, 255],
+                8,
+            );
+        }
+        // and to transparent below it
+        for a in [0x01, 0x40, 0x7f] {
+            assert_eq!(round_trip::<Argb1555>([200, 100, 50, a]), [0; 4]);
+        }
+        assert_eq!(Argb1555::EMPTY.0, 0);
+    }
+
+    #[test]
+    fn l8_round_trip() {
+        for v in 0..=255 {
+            assert_eq!(round_trip::<L8>([v, v, v, 255]), [v, v, v, 255]);
+        }
+        // rec. 709 luma weights
+        assert_eq!(L8::from_fbcolor(FBColor::from_rgba8(255, 0, 0, 255)).0, 53);
+        assert_eq!(L8::from_fbcolor(FBColor::from_rgba8(0, 255, 0, 255)).0, 182);
+        assert_eq!(L8::from_fbcolor(FBColor::from_rgba8(0, 0, 255, 255)).0, 18);
+    }
+
+    #[test]
+    fn packed_alpha_blending_matches_fbcolor() {
+        let src = FBColor::from_rgba8(255, 128, 0, 96);
+        let dst = FBColor::from_rgba8(0, 64, 255, 255);
+        let mut expected = dst;
+        crate::blend::blend_alpha(src, &mut expected);
+
+        let mut packed = Rgba8::from_fbcolor(dst);
+        Rgba8::blend_alpha(src, &mut packed);
+        assert_near(packed.0, expected.to_rgba8(), 1);
+    }
+}
@@ -1,12 +1,12 @@
-use crate::{BlendMode, Drawable, FBColor, I16Vec2};
+use crate::{BlendMode, Blender, Drawable, FBColor, I16Vec2, PixelFormat, Scalar};
 
-pub struct ColorVec2 {
+pub struct ColorVec2<B = BlendMode> {
     pub position: I16Vec2,
     pub color: FBColor,
-    pub blend_mode: BlendMode,
+    pub blend_mode: B,
 }
-impl ColorVec2 {
-    pub const fn new(position: I16Vec2, color: FBColor, blend_mode: BlendMode) -> Self {
+impl<B: Blender> ColorVec2<B> {
+    pub const fn new(position: I16Vec2, color: FBColor, blend_mode: B) -> Self {
         Self {
             position,
             color,
@@ -14,13 +14,13 @@ impl ColorVec2 {
         }
     }
 }
-impl Drawable for ColorVec2 {
-    fn draw(&self, renderer: &mut crate::Renderer) {
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for ColorVec2<B> {
+    fn draw(&self, renderer: &mut crate::Renderer<P, S>) {
         renderer.set(
             self.position.x,
             self.position.y,
-            self.color,
-            self.blend_mode,
+            renderer.decode_color(self.color),
+            &self.blend_mode,
         );
     }
 }
@@ -0,0 +1,290 @@
+use core::cmp::Ordering;
+
+use alloc::vec::Vec;
+
+use crate::scalar::ceil_i32;
+use crate::stroke::Point;
+use crate::{BlendMode, Blender, Drawable, FBColor, PixelFormat, Renderer, Scalar, Vec2};
+
+/// Decides which regions of self-intersecting or nested contours are inside.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FillRule {
+    /// Inside wherever the contours wind around a point at all, so holes need to
+    /// run in the opposite direction of their outline.
+    #[default]
+    NonZero,
+    /// Inside wherever a ray from a point crosses an odd number of edges,
+    /// so holes work regardless of direction.
+    EvenOdd,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct PolygonEdge<S> {
+    top: Point<S>,
+    bottom: Point<S>,
+    first_row: i32,
+    end_row: i32,
+    winding: i32,
+}
+
+pub struct Polygon<B = BlendMode> {
+    /// Each contour is closed automatically. Points are in pixels, with whole numbers
+    /// at pixel centers like those of [`Polyline`](crate::Polyline), so a polygon and
+    /// its outline line up.
+    pub contours: Vec<Vec<Vec2>>,
+    pub fill_rule: FillRule,
+    pub color: FBColor,
+    pub blend_mode: B,
+}
+impl<B: Blender> Polygon<B> {
+    pub fn new(
+        contours: Vec<Vec<Vec2>>,
+        fill_rule: FillRule,
+        color: FBColor,
+        blend_mode: B,
+    ) -> Self {
+        Self {
+            contours,
+            fill_rule,
+            color,
+            blend_mode,
+        }
+    }
+}
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for Polygon<B> {
+    fn draw(&self, renderer: &mut Renderer<P, S>) {
+        let color = renderer.decode_color(self.color);
+        let contours = self.contours.iter().map(|contour| {
+            contour
+                .iter()
+                .map(|p| [S::from_f32(p.x) + S::HALF, S::from_f32(p.y) + S::HALF])
+        });
+        fill_polygon(
+            renderer,
+            contours,
+            self.fill_rule,
+            &self.blend_mode,
+            |_, _| color,
+        );
+    }
+}
+
+/// Scanline fills closed contours, sampling at pixel centers. `color` is called
+/// with the center of every pixel filled.
+pub(crate) fn fill_polygon<P: PixelFormat, S: Scalar, B: Blender>(
+    renderer: &mut Renderer<P, S>,
+    contours: impl IntoIterator<Item = impl IntoIterator<Item = Point<S>>>,
+    fill_rule: FillRule,
+    blend_mode: &B,
+    color: impl Fn(S, S) -> FBColor,
+) {
+    // the renderer's edge buffer holds a single span per row, while polygons can
+    // cross a row any number of times, so they keep a list of edges instead. it's
+    // taken from the renderer while filling, so its allocation is reused
+    let mut edges = core::mem::take(renderer.polygon_edge_buffer_mut());
+    edges.clear();
+    let mut push_edge = |a: Point<S>, b: Point<S>| {
+        let (top, bottom, winding) = if a[1] < b[1] { (a, b, 1) } else { (b, a, -1) };
+        let first_row = ceil_i32(top[1] - S::HALF);
+        let end_row = ceil_i32(bottom[1] - S::HALF);
+        // horizontal edges, and edges between two pixel centers, never cross a row
+        if first_row < end_row {
+            edges.push(PolygonEdge {
+                top,
+                bottom,
+                first_row,
+                end_row,
+                winding,
+            });
+        }
+    };
+    for contour in contours {
+        let mut contour = contour.into_iter();
+        let Some(first) = contour.next() else {
+            continue;
+        };
+        let mut prev = first;
+        for point in contour {
+            push_edge(prev, point);
+            prev = point;
+        }
+        push_edge(prev, first);
+    }
+    edges.sort_unstable_by_key(|edge| edge.first_row);
+
+    let clip_min = renderer.clip_min();
+    let clip_max = renderer.clip_max();
+    let min_y = edges
+        .first()
+        .map_or(0, |edge| edge.first_row)
+        .max(clip_min.y as i32);
+    let max_y = edges
+        .iter()
+        .map(|edge| edge.end_row)
+        .max()
+        .unwrap_or(0)
+        .min(clip_max.y as i32);
+
+    let mut active: Vec<usize> = Vec::new();
+    let mut crossings: Vec<(S, i32)> = Vec::new();
+    let mut next_edge = 0;
+    for y in min_y..max_y {
+        while next_edge < edges.len() && edges[next_edge].first_row <= y {
+            active.push(next_edge);
+            next_edge += 1;
+        }
+        active.retain(|&i| edges[i].end_row > y);
+
+        let center_y = S::from_i32(y) + S::HALF;
+        crossings.clear();
+        crossings.extend(active.iter().map(|&i| {
+            let edge = &edges[i];
+            let t = (center_y - edge.top[1]) / (edge.bottom[1] - edge.top[1]);
+            (
+                edge.top[0] + t * (edge.bottom[0] - edge.top[0]),
+                edge.winding,
+            )
+        }));
+        crossings.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+        let mut winding = 0;
+        for pair in crossings.windows(2) {
+            winding += pair[0].1;
+            let inside = match fill_rule {
+                FillRule::NonZero => winding != 0,
+                FillRule::EvenOdd => winding % 2 != 0,
+            };
+            if !inside {
+                continue;
+            }
+            let start = ceil_i32(pair[0].0 - S::HALF).max(clip_min.x as i32);
+            let end = ceil_i32(pair[1].0 - S::HALF).min(clip_max.x as i32);
+            for x in start..end {
+                let c = color(S::from_i32(x) + S::HALF, center_y);
+                renderer.set_unchecked(x as i16, y as i16, c, blend_mode);
+            }
+        }
+    }
+    *renderer.polygon_edge_buffer_mut() = edges;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Drawable, FBColor};
+    extern crate std;
+
+    use ::test::Bencher;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::ffi::*;
+    use std::random::Random;
+
+    use crate::{BlendMode, Renderer, Vec2, ffi::*};
+
+    use super::{FillRule, Polygon};
+
+    fn filled(polygon: &Polygon) -> Vec<(usize, usize)> {
+        let mut renderer = Renderer::new(16, 16);
+        polygon.draw(&mut renderer);
+        let mut filled = Vec::new();
+        for (y, row) in renderer.rows().enumerate() {
+            for (x, pixel) in row.iter().enumerate() {
+                if pixel.a() > 0.0 {
+                    filled.push((x, y));
+                }
+            }
+        }
+        filled
+    }
+
+    #[test]
+    fn fill_rules() {
+        let square = |x: f32, y: f32, size: f32| {
+            vec![
+                Vec2::new(x, y),
+                Vec2::new(x + size, y),
+                Vec2::new(x + size, y + size),
+                Vec2::new(x, y + size),
+            ]
+        };
+        let in_square = |(x, y): (usize, usize), min: usize, max: usize| {
+            (min..max).contains(&x) && (min..max).contains(&y)
+        };
+        let overlap = |(x, y): (usize, usize)| (4..8).contains(&x) && (4..8).contains(&y);
+
+        // two squares wound the same way, overlapping on 4..8
+        let mut polygon = Polygon::new(
+            vec![square(0.0, 0.0, 8.0), square(4.0, 4.0, 8.0)],
+            FillRule::NonZero,
+            FBColor::WHITE,
+            BlendMode::Opaque,
+        );
+        let union: Vec<_> = (0..16)
+            .flat_map(|y| (0..16).map(move |x| (x, y)))
+            .filter(|&p| in_square(p, 0, 8) || in_square(p, 4, 12))
+            .collect();
+        assert_eq!(filled(&polygon), union);
+
+        polygon.fill_rule = FillRule::EvenOdd;
+        let without_overlap: Vec<_> = union.iter().copied().filter(|&p| !overlap(p)).collect();
+        assert_eq!(filled(&polygon), without_overlap);
+
+        // a pentagram winds around its center twice, and around its tips once
+        polygon.contours = vec![
+            [
+                (8.0, 1.0),
+                (12.0, 14.0),
+                (1.0, 6.0),
+                (15.0, 6.0),
+                (4.0, 14.0),
+            ]
+            .map(|(x, y)| Vec2::new(x, y))
+            .to_vec(),
+        ];
+        polygon.fill_rule = FillRule::NonZero;
+        let non_zero = filled(&polygon);
+        polygon.fill_rule = FillRule::EvenOdd;
+        let even_odd = filled(&polygon);
+        for tip in [(8, 3), (13, 6), (3, 6)] {
+            assert!(non_zero.contains(&tip) && even_odd.contains(&tip));
+        }
+        assert!(non_zero.contains(&(8, 8)) && !even_odd.contains(&(8, 8)));
+        assert!(even_odd.iter().all(|p| non_zero.contains(p)));
+    }
+
+    #[bench]
+    fn bmp_polygon(bencher: &mut Bencher) {
+        let b = test::black_box(unsafe { bm_create(128, 128) });
+        let mut rand = std::random::DefaultRandomSource;
+        let points = &mut [BmPoint { x: 0, y: 0 }; 8];
+        bencher.iter(|| unsafe {
+            for point in points.iter_mut() {
+                point.x = c_int::random(&mut rand).clamp(-32, 128 + 32);
+                point.y = c_int::random(&mut rand).clamp(-32, 128 + 32);
+            }
+            bm_fillpoly(b, points.as_ptr(), points.len() as c_uint)
+        });
+        unsafe {
+            bm_free(b);
+        }
+    }
+
+    #[bench]
+    fn our_polygon(bencher: &mut Bencher) {
+        let mut renderer = test::black_box(Renderer::new(128, 128));
+        let mut rand = std::random::DefaultRandomSource;
+        let mut polygon = Polygon::new(
+            vec![vec![Vec2::ZERO; 8]],
+            FillRule::EvenOdd,
+            FBColor::MAGENTA,
+            BlendMode::Opaque,
+        );
+        bencher.iter(|| {
+            for point in polygon.contours[0].iter_mut() {
+                point.x = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+                point.y = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            }
+            polygon.draw(&mut renderer);
+        })
+    }
+}
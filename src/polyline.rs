@@ -0,0 +1,118 @@
+use alloc::vec::Vec;
+
+use crate::stroke::{fill_pieces, stroke_polyline};
+use crate::{
+    BlendMode, Blender, Drawable, FBColor, LineCap, LineJoin, PixelFormat, Renderer, Scalar, Vec2,
+};
+
+pub struct Polyline<B = BlendMode> {
+    /// In pixels, with whole numbers at pixel centers like the end points of
+    /// [`Line`](crate::Line).
+    pub points: Vec<Vec2>,
+    /// Connects the last point back to the first, with a join instead of caps.
+    pub closed: bool,
+    pub width: u16,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub color: FBColor,
+    pub blend_mode: B,
+}
+impl<B: Blender> Polyline<B> {
+    pub fn new(
+        points: Vec<Vec2>,
+        width: u16,
+        cap: LineCap,
+        join: LineJoin,
+        color: FBColor,
+        blend_mode: B,
+    ) -> Self {
+        Self {
+            points,
+            closed: false,
+            width,
+            cap,
+            join,
+            color,
+            blend_mode,
+        }
+    }
+}
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for Polyline<B> {
+    fn draw(&self, renderer: &mut Renderer<P, S>) {
+        if self.width == 0 {
+            return;
+        }
+        // stroke through pixel centers, like `Line`
+        let points: Vec<_> = self
+            .points
+            .iter()
+            .map(|p| [S::from_f32(p.x) + S::HALF, S::from_f32(p.y) + S::HALF])
+            .collect();
+        let mut pieces = Vec::new();
+        stroke_polyline(
+            &points,
+            self.closed,
+            S::from_ratio(self.width as i32, 2),
+            self.cap,
+            self.join,
+            &mut pieces,
+        );
+
+        let color = renderer.decode_color(self.color);
+        fill_pieces(renderer, &pieces, &self.blend_mode, |_, _| color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Drawable, FBColor};
+    extern crate std;
+
+    use ::test::Bencher;
+    use alloc::vec;
+    use core::ffi::*;
+    use std::random::Random;
+
+    use crate::{BlendMode, LineCap, LineJoin, Renderer, Vec2, ffi::*};
+
+    use super::Polyline;
+
+    #[bench]
+    fn bmp_polyline(bencher: &mut Bencher) {
+        let b = test::black_box(unsafe { bm_create(128, 128) });
+        let mut rand = std::random::DefaultRandomSource;
+        let points = &mut [BmPoint { x: 0, y: 0 }; 8];
+        bencher.iter(|| unsafe {
+            for point in points.iter_mut() {
+                point.x = c_int::random(&mut rand).clamp(-32, 128 + 32);
+                point.y = c_int::random(&mut rand).clamp(-32, 128 + 32);
+            }
+            bm_poly(b, points.as_ptr(), points.len() as c_uint)
+        });
+        unsafe {
+            bm_free(b);
+        }
+    }
+
+    #[bench]
+    fn our_polyline(bencher: &mut Bencher) {
+        let mut renderer = test::black_box(Renderer::new(128, 128));
+        let mut rand = std::random::DefaultRandomSource;
+        let mut polyline = Polyline::new(
+            vec![Vec2::ZERO; 8],
+            1,
+            LineCap::Butt,
+            LineJoin::Miter,
+            FBColor::WHITE,
+            BlendMode::Opaque,
+        );
+        polyline.closed = true;
+        bencher.iter(|| {
+            for point in polyline.points.iter_mut() {
+                point.x = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+                point.y = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            }
+            polyline.draw(&mut renderer);
+        })
+    }
+}
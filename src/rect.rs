@@ -1,29 +1,61 @@
-use crate::{BlendMode, ColorMode, Drawable, I16Vec2, bilinear_4_colors};
+use core::ops::Range;
+
+use glam::IVec2;
+
+use crate::scalar::ceil_i32;
+use crate::{
+    BlendMode, Blender, ColorMode, Drawable, I16Vec2, PixelFormat, Scalar, StrokeAlignment,
+    bilinear_4_colors,
+};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Rect {
     pub position: I16Vec2,
     pub size: I16Vec2,
 }
+impl Rect {
+    pub const fn new(position: I16Vec2, size: I16Vec2) -> Self {
+        Self { position, size }
+    }
+
+    pub fn min_point(&self) -> I16Vec2 {
+        self.position.min(self.position.saturating_add(self.size))
+    }
+
+    pub fn max_point(&self) -> I16Vec2 {
+        self.position.max(self.position.saturating_add(self.size))
+    }
+}
 
-pub struct ColorRect {
+pub struct ColorRect<B = BlendMode> {
     pub rect: Rect,
     pub color: ColorMode<4>,
-    pub blend_mode: BlendMode,
+    pub blend_mode: B,
+    pub fill: bool,
+    /// Outline width, when not filled.
+    pub width: u16,
+    pub alignment: StrokeAlignment,
+    /// Corner radii, ordered like per point colors: top left, top right,
+    /// bottom left, bottom right.
+    pub radii: [u16; 4],
 }
-impl ColorRect {
-    pub fn new(rect: Rect, color: ColorMode<4>, blend_mode: BlendMode) -> Self {
+impl<B: Blender> ColorRect<B> {
+    pub fn new(rect: Rect, color: ColorMode<4>, blend_mode: B) -> Self {
         Self {
             rect,
             color,
             blend_mode,
+            fill: true,
+            width: 1,
+            alignment: StrokeAlignment::Inner,
+            radii: [0; 4],
         }
     }
 }
-impl Drawable for ColorRect {
-    fn draw(&self, renderer: &mut crate::Renderer) {
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for ColorRect<B> {
+    fn draw(&self, renderer: &mut crate::Renderer<P, S>) {
         let single_color;
-        let colors = match self.color {
+        let colors = match self.color.map(|c| renderer.decode_color(c)) {
             ColorMode::Solid(c) => {
                 single_color = true;
                 [c, c, c, c]
@@ -34,35 +66,135 @@ impl Drawable for ColorRect {
             }
         };
 
-        let min_point = self.rect.position.min(self.rect.position + self.rect.size);
-        let max_point = self.rect.position.max(self.rect.position + self.rect.size);
+        let rect_min = self.rect.min_point();
+        let rect_max = self.rect.max_point();
+        let radii = self.radii.map(|r| r as i32);
 
-        let min_x = min_point.x.max(0);
-        let max_x = max_point.x.min(renderer.width());
-        let min_y = min_point.y.max(0);
-        let max_y = max_point.y.min(renderer.height());
+        // outlines are drawn between the outer rect and the hole
+        let (outer, hole) = if self.fill {
+            (RoundedBounds::<S>::new(rect_min, rect_max, radii, 0), None)
+        } else {
+            let (outer, inner) = self.alignment.offsets(self.width);
+            (
+                RoundedBounds::<S>::new(rect_min, rect_max, radii, outer),
+                Some(RoundedBounds::<S>::new(rect_min, rect_max, radii, inner)),
+            )
+        };
+
+        let clip_min = renderer.clip_min();
+        let clip_max = renderer.clip_max();
+        let min_y = outer.min.y.max(clip_min.y);
+        let max_y = outer.max.y.min(clip_max.y);
 
-        let size = self.rect.size.abs();
+        let size = self.rect.size.abs().max(I16Vec2::ONE);
 
         for y in min_y..max_y {
-            for x in min_x..max_x {
+            let span = outer.span(y);
+            let hole = match &hole {
+                Some(hole) => hole.span(y),
+                None => 0..0,
+            };
+            for x in span.start.max(clip_min.x)..span.end.min(clip_max.x) {
+                if hole.contains(&x) {
+                    continue;
+                }
                 renderer.set(
                     x,
                     y,
                     if single_color {
                         colors[0]
                     } else {
-                        let x = x as f32 / size.x as f32;
-                        let y = y as f32 / size.y as f32;
+                        // outer outlines keep the colors of the rect's edges
+                        let x = (x - rect_min.x).clamp(0, size.x);
+                        let y = (y - rect_min.y).clamp(0, size.y);
+                        let x = S::from_ratio(x as i32, size.x as i32);
+                        let y = S::from_ratio(y as i32, size.y as i32);
                         bilinear_4_colors(x, y, colors[0], colors[1], colors[2], colors[3])
                     },
-                    self.blend_mode,
+                    &self.blend_mode,
                 );
             }
         }
     }
 }
 
+// a rect with rounded corners, ordered like `ColorRect::radii`
+struct RoundedBounds<S> {
+    min: I16Vec2,
+    max: I16Vec2,
+    radii: [S; 4],
+}
+impl<S: Scalar> RoundedBounds<S> {
+    // grows the rect by `offset` pixels on every side, and its rounded corners with it
+    fn new(min: I16Vec2, max: I16Vec2, radii: [i32; 4], offset: i32) -> Self {
+        let offset = I16Vec2::splat(offset.clamp(i16::MIN as i32, i16::MAX as i32) as i16);
+        let min = min.saturating_sub(offset);
+        let max = max.saturating_add(offset);
+        let size = (max.as_ivec2() - min.as_ivec2()).max(IVec2::ZERO);
+        let radii = radii.map(|r| {
+            if r > 0 {
+                (r + offset.x as i32).max(0)
+            } else {
+                0
+            }
+        });
+
+        // shrink all corners evenly when neighbors would overlap, like css does
+        let [top_left, top_right, bottom_left, bottom_right] = radii;
+        let mut fit = (1, 1);
+        for (sum, length) in [
+            (top_left + top_right, size.x),
+            (bottom_left + bottom_right, size.x),
+            (top_left + bottom_left, size.y),
+            (top_right + bottom_right, size.y),
+        ] {
+            if sum > 0 && length * fit.1 < sum * fit.0 {
+                fit = (length, sum);
+            }
+        }
+        Self {
+            min,
+            max,
+            radii: radii.map(|r| S::from_i32(r) * S::from_ratio(fit.0, fit.1)),
+        }
+    }
+
+    // pixels of row `y` that are inside, sampled at their centers
+    fn span(&self, y: i16) -> Range<i16> {
+        if y < self.min.y || y >= self.max.y {
+            return 0..0;
+        }
+        let center_y = S::from_i32(y as i32) + S::HALF;
+        let [top_left, top_right, bottom_left, bottom_right] = self.radii;
+        let top = S::from_i32(self.min.y as i32);
+        let bottom = S::from_i32(self.max.y as i32);
+
+        // how far the corner of `radius` cuts into the row
+        let inset = |radius: S, top_radius: bool| {
+            let depth = if top_radius {
+                top + radius - center_y
+            } else {
+                center_y - (bottom - radius)
+            };
+            if depth <= S::ZERO {
+                return S::ZERO;
+            }
+            let t = depth / radius;
+            let rest = S::ONE - t * t;
+            radius * (S::ONE - if rest > S::ZERO { rest.sqrt() } else { S::ZERO })
+        };
+        let (left, right) = if center_y - top < bottom - center_y {
+            (inset(top_left, true), inset(top_right, true))
+        } else {
+            (inset(bottom_left, false), inset(bottom_right, false))
+        };
+
+        let start = ceil_i32(S::from_i32(self.min.x as i32) + left - S::HALF);
+        let end = ceil_i32(S::from_i32(self.max.x as i32) - right - S::HALF);
+        start as i16..end as i16
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Drawable, FBColor};
@@ -1,39 +1,127 @@
-use core::ops::Range;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut, Range};
 
 use alloc::vec;
 use alloc::vec::Vec;
 
-use crate::{BlendMode, Drawable, FBColor, blend_alpha, blend_none};
+use crate::polygon::PolygonEdge;
+use crate::{BlendMode, Blender, Drawable, FBColor, I16Vec2, PixelFormat, Rect, Scalar};
 
 macro_rules! fb_idx {
     ($renderer:expr, $x:expr, $y:expr) => {
-        ($y as u32 * $renderer.width() as u32 + $x as u32) as usize
+        $y as usize * $renderer.stride + $x as usize
     };
 }
 
-type BlendFunc = fn(src: FBColor, dst: &mut FBColor);
+/// Color space that interpolation and blending happen in.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BlendSpace {
+    /// Colors are used as given, so sRGB content is blended in gamma space.
+    #[default]
+    Gamma,
+    /// Colors and the framebuffer are sRGB encoded. They're decoded to linear light
+    /// for interpolation and blending, and encoded again when written back.
+    Linear,
+}
+
+enum FrameBuffer<'a, P> {
+    Owned(Vec<P>),
+    Borrowed(&'a mut [P]),
+}
+impl<P> Deref for FrameBuffer<'_, P> {
+    type Target = [P];
 
-const fn get_blend_func(blend_mode: BlendMode) -> BlendFunc {
-    match blend_mode {
-        BlendMode::Opaque => blend_none,
-        BlendMode::Alpha => blend_alpha,
+    fn deref(&self) -> &Self::Target {
+        match self {
+            FrameBuffer::Owned(fb) => fb,
+            FrameBuffer::Borrowed(fb) => fb,
+        }
+    }
+}
+impl<P> DerefMut for FrameBuffer<'_, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            FrameBuffer::Owned(fb) => fb,
+            FrameBuffer::Borrowed(fb) => fb,
+        }
     }
 }
 
-#[derive(Clone)]
-pub struct Renderer {
-    fb: Vec<FBColor>,
+pub struct Renderer<'a, P: PixelFormat = FBColor, S: Scalar = f32> {
+    fb: FrameBuffer<'a, P>,
     width: u16,
     height: u16,
+    stride: usize,
     work_edges: Vec<Range<i16>>,
+    work_polygon_edges: Vec<PolygonEdge<S>>,
+    clip_min: I16Vec2,
+    clip_max: I16Vec2,
+    clip_stack: Vec<(I16Vec2, I16Vec2)>,
+    blend_space: BlendSpace,
+    scalar: PhantomData<S>,
 }
-impl Renderer {
+impl Renderer<'static> {
     pub fn new(width: u16, height: u16) -> Self {
+        Self::with_format(width, height)
+    }
+}
+impl<P: PixelFormat> Renderer<'static, P> {
+    pub fn with_format(width: u16, height: u16) -> Self {
+        Self::from_parts(
+            FrameBuffer::Owned(vec![P::EMPTY; width as usize * height as usize]),
+            width,
+            height,
+            width as usize,
+        )
+    }
+}
+impl<'a, P: PixelFormat> Renderer<'a, P> {
+    /// Renders directly into a caller-owned buffer.
+    ///
+    /// `stride` is the distance in pixels between the starts of two rows, so a
+    /// sub-region of a larger surface can be targeted by slicing from its top-left pixel.
+    pub fn with_buffer(fb: &'a mut [P], width: u16, height: u16, stride: usize) -> Self {
+        assert!(stride >= width as usize, "stride is smaller than width");
+        if height > 0 {
+            assert!(
+                fb.len() >= stride * (height as usize - 1) + width as usize,
+                "framebuffer is too small for the given dimensions"
+            );
+        }
+        Self::from_parts(FrameBuffer::Borrowed(fb), width, height, stride)
+    }
+
+    fn from_parts(fb: FrameBuffer<'a, P>, width: u16, height: u16, stride: usize) -> Self {
         Self {
-            fb: vec![FBColor::EMPTY; width as usize * height as usize],
+            fb,
             width,
             height,
+            stride,
             work_edges: vec![i16::MAX..i16::MIN; height as usize],
+            work_polygon_edges: Vec::new(),
+            clip_min: I16Vec2::ZERO,
+            clip_max: I16Vec2::new(width as i16, height as i16),
+            clip_stack: Vec::new(),
+            blend_space: BlendSpace::Gamma,
+            scalar: PhantomData,
+        }
+    }
+}
+impl<'a, P: PixelFormat, S: Scalar> Renderer<'a, P, S> {
+    /// Switches the number type used for subpixel calculations.
+    pub fn with_scalar<T: Scalar>(self) -> Renderer<'a, P, T> {
+        Renderer {
+            fb: self.fb,
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+            work_edges: self.work_edges,
+            work_polygon_edges: Vec::new(),
+            clip_min: self.clip_min,
+            clip_max: self.clip_max,
+            clip_stack: self.clip_stack,
+            blend_space: self.blend_space,
+            scalar: PhantomData,
         }
     }
 
@@ -45,12 +133,78 @@ impl Renderer {
         self.height as i16
     }
 
-    pub fn fb(&self) -> &Vec<FBColor> {
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn fb(&self) -> &[P] {
         &self.fb
     }
 
+    pub fn fb_mut(&mut self) -> &mut [P] {
+        &mut self.fb
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[P]> {
+        // indexed rather than chunked, since empty buffers can have a stride of zero
+        (0..self.height as usize).map(|y| {
+            let start = y * self.stride;
+            &self.fb[start..start + self.width as usize]
+        })
+    }
+
+    /// Copies the framebuffer out as straight alpha RGBA8.
     pub fn fb_rgba8(&self) -> Vec<u8> {
-        self.fb.iter().flat_map(|fbc| fbc.to_rgba8()).collect()
+        self.rows()
+            .flat_map(|row| {
+                row.iter()
+                    .flat_map(|p| p.to_fbcolor().unpremultiplied().to_rgba8())
+            })
+            .collect()
+    }
+
+    pub fn blend_space(&self) -> BlendSpace {
+        self.blend_space
+    }
+
+    pub fn set_blend_space(&mut self, blend_space: BlendSpace) {
+        self.blend_space = blend_space;
+    }
+
+    /// Converts a color from its sRGB encoding to the space used for interpolation
+    /// and blending. Colors passed to [`Renderer::set`] are expected in that space.
+    pub fn decode_color(&self, color: FBColor) -> FBColor {
+        match self.blend_space {
+            BlendSpace::Gamma => color,
+            BlendSpace::Linear => color.srgb_to_linear(),
+        }
+    }
+
+    /// Restricts drawing to `rect`, intersected with the current clip rect,
+    /// until the matching [`Renderer::pop_clip`].
+    pub fn push_clip(&mut self, rect: Rect) {
+        self.clip_stack.push((self.clip_min, self.clip_max));
+        self.clip_min = rect.min_point().max(self.clip_min);
+        self.clip_max = rect.max_point().min(self.clip_max).max(self.clip_min);
+    }
+
+    pub fn pop_clip(&mut self) {
+        if let Some((clip_min, clip_max)) = self.clip_stack.pop() {
+            self.clip_min = clip_min;
+            self.clip_max = clip_max;
+        }
+    }
+
+    pub fn clip_rect(&self) -> Rect {
+        Rect::new(self.clip_min, self.clip_max - self.clip_min)
+    }
+
+    pub(crate) fn clip_min(&self) -> I16Vec2 {
+        self.clip_min
+    }
+
+    pub(crate) fn clip_max(&self) -> I16Vec2 {
+        self.clip_max
     }
 
     pub(crate) fn edge_buffer(&self) -> &[Range<i16>] {
@@ -61,40 +215,156 @@ impl Renderer {
         &mut self.work_edges
     }
 
+    pub(crate) fn polygon_edge_buffer_mut(&mut self) -> &mut Vec<PolygonEdge<S>> {
+        &mut self.work_polygon_edges
+    }
+
     pub fn fill(&mut self, color: FBColor, blend_mode: BlendMode) {
+        let (min, max) = (self.clip_min, self.clip_max);
         if blend_mode == BlendMode::Opaque {
-            self.fb.fill(color);
+            // the framebuffer is stored encoded, so no decoding is needed
+            let pixel = P::from_fbcolor(color.premultiplied());
+            for y in min.y..max.y {
+                let row = fb_idx!(self, 0, y);
+                self.fb[row + min.x as usize..row + max.x as usize].fill(pixel);
+            }
             return;
         }
-        for y in 0..self.height {
-            for x in 0..self.width {
-                self.set(x as i16, y as i16, color, blend_mode);
+        let color = self.decode_color(color);
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                self.set_unchecked(x, y, color, blend_mode);
             }
         }
     }
 
-    pub fn set(&mut self, x: i16, y: i16, color: FBColor, blend_mode: BlendMode) {
-        if x < 0 || x >= self.width as i16 || y < 0 || y >= self.height as i16 {
+    pub fn set<B: Blender>(&mut self, x: i16, y: i16, color: FBColor, blender: B) {
+        if x < self.clip_min.x
+            || x >= self.clip_max.x
+            || y < self.clip_min.y
+            || y >= self.clip_max.y
+        {
             return;
         }
-        let blend_func = get_blend_func(blend_mode);
-        let idx = fb_idx!(self, x, y);
-        blend_func(color, &mut self.fb[idx]);
+        self.set_unchecked(x, y, color, blender);
     }
 
-    pub fn set_unchecked(&mut self, x: i16, y: i16, color: FBColor, blend_mode: BlendMode) {
-        let blend_func = get_blend_func(blend_mode);
+    pub fn set_unchecked<B: Blender>(&mut self, x: i16, y: i16, color: FBColor, blender: B) {
         let idx = fb_idx!(self, x, y);
-        blend_func(color, &mut self.fb[idx]);
+        let dst = &mut self.fb[idx];
+        match self.blend_space {
+            BlendSpace::Gamma => blender.blend_pixel(color, dst),
+            BlendSpace::Linear => {
+                let mut dst_color = dst
+                    .to_fbcolor()
+                    .unpremultiplied()
+                    .srgb_to_linear()
+                    .premultiplied();
+                blender.blend(color, &mut dst_color);
+                *dst =
+                    P::from_fbcolor(dst_color.unpremultiplied().linear_to_srgb().premultiplied());
+            }
+        }
     }
 
-    pub fn draw(&mut self, drawable: &dyn Drawable) {
+    pub fn draw(&mut self, drawable: &dyn Drawable<P, S>) {
         drawable.draw(self);
     }
 
-    pub fn draw_multiple(&mut self, drawables: &[&dyn Drawable]) {
+    pub fn draw_multiple(&mut self, drawables: &[&dyn Drawable<P, S>]) {
         for drawable in drawables {
             self.draw(*drawable);
         }
     }
 }
+impl<P: PixelFormat, S: Scalar> Clone for Renderer<'_, P, S> {
+    fn clone(&self) -> Self {
+        Self {
+            fb: FrameBuffer::Owned(self.fb.to_vec()),
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+            work_edges: self.work_edges.clone(),
+            work_polygon_edges: Vec::new(),
+            clip_min: self.clip_min,
+            clip_max: self.clip_max,
+            clip_stack: self.clip_stack.clone(),
+            blend_space: self.blend_space,
+            scalar: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{BlendMode, FBColor, I16Vec2, Rect};
+
+    use super::{BlendSpace, Renderer};
+
+    fn clip(renderer: &Renderer) -> (I16Vec2, I16Vec2) {
+        let rect = renderer.clip_rect();
+        (rect.position, rect.size)
+    }
+
+    fn drawn(renderer: &Renderer) -> Vec<(usize, usize)> {
+        let mut drawn = Vec::new();
+        for (y, row) in renderer.rows().enumerate() {
+            for (x, pixel) in row.iter().enumerate() {
+                if pixel.a() > 0.0 {
+                    drawn.push((x, y));
+                }
+            }
+        }
+        drawn
+    }
+
+    #[test]
+    fn nested_clips() {
+        let mut renderer = Renderer::new(8, 8);
+        let full = (I16Vec2::ZERO, I16Vec2::new(8, 8));
+        assert_eq!(clip(&renderer), full);
+
+        renderer.push_clip(Rect::new(I16Vec2::new(1, 1), I16Vec2::new(5, 5)));
+        assert_eq!(clip(&renderer), (I16Vec2::new(1, 1), I16Vec2::new(5, 5)));
+        // nested clips intersect with the ones around them
+        renderer.push_clip(Rect::new(I16Vec2::new(3, -2), I16Vec2::new(10, 4)));
+        assert_eq!(clip(&renderer), (I16Vec2::new(3, 1), I16Vec2::new(3, 1)));
+        // and can end up empty
+        renderer.push_clip(Rect::new(I16Vec2::new(7, 7), I16Vec2::new(1, 1)));
+        assert_eq!(clip(&renderer).1, I16Vec2::ZERO);
+        renderer.fill(FBColor::WHITE, BlendMode::Opaque);
+        assert!(drawn(&renderer).is_empty());
+
+        renderer.pop_clip();
+        renderer.fill(FBColor::WHITE, BlendMode::Opaque);
+        assert_eq!(drawn(&renderer), [(3, 1), (4, 1), (5, 1)]);
+
+        renderer.pop_clip();
+        assert_eq!(clip(&renderer), (I16Vec2::new(1, 1), I16Vec2::new(5, 5)));
+        renderer.pop_clip();
+        assert_eq!(clip(&renderer), full);
+        // popping more than was pushed keeps the whole framebuffer
+        renderer.pop_clip();
+        assert_eq!(clip(&renderer), full);
+    }
+
+    #[test]
+    fn blend_spaces() {
+        // half white over black is half as bright in linear light, which encodes to 0.735
+        for (blend_space, expected) in [(BlendSpace::Gamma, 0.5), (BlendSpace::Linear, 0.7354)] {
+            let mut renderer = Renderer::new(1, 1);
+            renderer.set_blend_space(blend_space);
+            renderer.fill(FBColor::BLACK, BlendMode::Opaque);
+            renderer.fill(FBColor::WHITE.with_a(0.5), BlendMode::Alpha);
+            let pixel = renderer.fb()[0];
+            assert!(
+                (pixel.r() - expected).abs() < 1e-3,
+                "{blend_space:?}: {} isn't {expected}",
+                pixel.r()
+            );
+            assert_eq!(pixel.a(), 1.0);
+        }
+    }
+}
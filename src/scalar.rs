@@ -0,0 +1,353 @@
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Number type used for subpixel calculations: color interpolation weights,
+/// triangle barycentrics, line gradients and sprite rotozoom setup.
+///
+/// Color channels themselves are always stored as `f32`; only the math that
+/// positions and weights them goes through the scalar type.
+///
+/// Compared to `f32`, both `f64` and [`Fixed16`] stay within one 8-bit step per channel
+/// for interpolated shape colors. Rotozoomed sprites may pick the neighbouring texel where
+/// a sample lands right on a texel boundary. [`Fixed16`] only holds these bounds while
+/// intermediate values stay inside its range of ±32767.
+pub trait Scalar:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const HALF: Self;
+    const TAU: Self;
+
+    fn from_i32(value: i32) -> Self;
+
+    /// `num / den`, computed without first converting `num` and `den` separately,
+    /// so large integer operands don't overflow narrow scalar types.
+    fn from_ratio(num: i32, den: i32) -> Self;
+
+    fn from_f32(value: f32) -> Self;
+
+    fn to_f32(self) -> f32;
+
+    /// Truncates towards zero, like an `as` cast.
+    fn to_i32(self) -> i32;
+
+    /// Converts to a 16.16 fixed point integer, saturating on overflow.
+    fn to_fixed16(self) -> i32;
+
+    fn sqrt(self) -> Self;
+
+    fn sin_cos(self) -> (Self, Self);
+}
+
+macro_rules! impl_float_scalar {
+    ($($float:ty),+) => {
+        $(
+            impl Scalar for $float {
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+                const HALF: Self = 0.5;
+                const TAU: Self = core::f64::consts::TAU as Self;
+
+                fn from_i32(value: i32) -> Self {
+                    value as Self
+                }
+
+                fn from_ratio(num: i32, den: i32) -> Self {
+                    num as Self / den as Self
+                }
+
+                fn from_f32(value: f32) -> Self {
+                    value as Self
+                }
+
+                fn to_f32(self) -> f32 {
+                    self as f32
+                }
+
+                fn to_i32(self) -> i32 {
+                    self as i32
+                }
+
+                fn to_fixed16(self) -> i32 {
+                    (self * 65536.0) as i32
+                }
+
+                fn sqrt(self) -> Self {
+                    <$float>::sqrt(self)
+                }
+
+                fn sin_cos(self) -> (Self, Self) {
+                    <$float>::sin_cos(self)
+                }
+            }
+        )+
+    };
+}
+
+impl_float_scalar!(f32, f64);
+
+/// 16.16 signed fixed point number, for targets without an FPU.
+///
+/// Arithmetic saturates instead of wrapping.
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct Fixed16(pub i32);
+impl Fixed16 {
+    const PI: Self = Self(205887);
+    const FRAC_PI_2: Self = Self(102944);
+
+    const fn saturate(value: i64) -> Self {
+        Self(if value > i32::MAX as i64 {
+            i32::MAX
+        } else if value < i32::MIN as i64 {
+            i32::MIN
+        } else {
+            value as i32
+        })
+    }
+
+    // taylor series, accurate to a few ulps on -pi/2..=pi/2
+    fn sin_reduced(x: Self) -> Self {
+        let x2 = x * x;
+        let mut term = x;
+        let mut sum = x;
+        for divisor in [6, 20, 42, 72] {
+            term = -(term * x2 / Self::from_i32(divisor));
+            sum = sum + term;
+        }
+        sum
+    }
+
+    fn sin(self) -> Self {
+        // reduce to -pi..pi, then fold into -pi/2..=pi/2
+        let mut x = Self((self.0 as i64 % Self::TAU.0 as i64) as i32);
+        if x > Self::PI {
+            x = x - Self::TAU;
+        } else if x < -Self::PI {
+            x = x + Self::TAU;
+        }
+        if x > Self::FRAC_PI_2 {
+            x = Self::PI - x;
+        } else if x < -Self::FRAC_PI_2 {
+            x = -Self::PI - x;
+        }
+        Self::sin_reduced(x)
+    }
+}
+impl Add for Fixed16 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+impl Sub for Fixed16 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+impl Mul for Fixed16 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::saturate((self.0 as i64 * rhs.0 as i64) >> 16)
+    }
+}
+impl Div for Fixed16 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.0 == 0 {
+            return Self(if self.0 < 0 { i32::MIN } else { i32::MAX });
+        }
+        Self::saturate(((self.0 as i64) << 16) / rhs.0 as i64)
+    }
+}
+impl Neg for Fixed16 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(self.0.saturating_neg())
+    }
+}
+impl Scalar for Fixed16 {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1 << 16);
+    const HALF: Self = Self(1 << 15);
+    const TAU: Self = Self(411775);
+
+    fn from_i32(value: i32) -> Self {
+        Self::saturate((value as i64) << 16)
+    }
+
+    fn from_ratio(num: i32, den: i32) -> Self {
+        if den == 0 {
+            return Self(if num < 0 { i32::MIN } else { i32::MAX });
+        }
+        Self::saturate(((num as i64) << 16) / den as i64)
+    }
+
+    fn from_f32(value: f32) -> Self {
+        Self((value * 65536.0) as i32)
+    }
+
+    fn to_f32(self) -> f32 {
+        self.0 as f32 / 65536.0
+    }
+
+    fn to_i32(self) -> i32 {
+        if self.0 < 0 {
+            -(-(self.0 as i64) >> 16) as i32
+        } else {
+            self.0 >> 16
+        }
+    }
+
+    fn to_fixed16(self) -> i32 {
+        self.0
+    }
+
+    fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Self::ZERO;
+        }
+        Self(((self.0 as u64) << 16).isqrt() as i32)
+    }
+
+    fn sin_cos(self) -> (Self, Self) {
+        (self.sin(), (self + Self::FRAC_PI_2).sin())
+    }
+}
+
+pub(crate) fn floor_i32<S: Scalar>(value: S) -> i32 {
+    let truncated = value.to_i32();
+    if S::from_i32(truncated) > value {
+        truncated - 1
+    } else {
+        truncated
+    }
+}
+
+pub(crate) fn ceil_i32<S: Scalar>(value: S) -> i32 {
+    let truncated = value.to_i32();
+    if S::from_i32(truncated) < value {
+        truncated + 1
+    } else {
+        truncated
+    }
+}
+
+/// Fractional bits of the fixed point positions that shapes are rasterized at.
+pub(crate) const SUBPIXEL_BITS: u32 = 4;
+pub(crate) const SUBPIXEL: i64 = 1 << SUBPIXEL_BITS;
+
+// far enough out that no clip rect reaches it, close enough that products of a few
+// subpixel positions fit in an i64
+const SUBPIXEL_LIMIT: i64 = 1 << 24;
+
+/// Rounds a position in pixels to the nearest subpixel.
+pub(crate) fn to_subpixel(value: f32) -> i64 {
+    let limit = SUBPIXEL_LIMIT as f32;
+    floor_i32((value * SUBPIXEL as f32 + 0.5).clamp(-limit, limit)) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::{Fixed16, Scalar, ceil_i32, floor_i32};
+
+    // the one 8-bit step per channel that fixed point results stay within
+    const STEP: f32 = 1.0 / 255.0;
+
+    const VALUES: [f32; 12] = [
+        -100.5, -3.25, -1.0, -0.3, 0.0, 0.1, 0.5, 1.0, 2.75, 37.125, 180.0, 1000.0,
+    ];
+
+    fn assert_close(fixed: Fixed16, expected: f32, what: &str) {
+        let tolerance = STEP * expected.abs().max(1.0);
+        assert!(
+            (fixed.to_f32() - expected).abs() <= tolerance,
+            "{what}: {} isn't within {tolerance} of {expected}",
+            fixed.to_f32()
+        );
+    }
+
+    #[test]
+    fn arithmetic_matches_f32() {
+        for a in VALUES {
+            for b in VALUES {
+                let (fa, fb) = (Fixed16::from_f32(a), Fixed16::from_f32(b));
+                assert_close(fa + fb, a + b, &std::format!("{a} + {b}"));
+                assert_close(fa - fb, a - b, &std::format!("{a} - {b}"));
+                if (a * b).abs() < 32767.0 {
+                    assert_close(fa * fb, a * b, &std::format!("{a} * {b}"));
+                }
+                if b != 0.0 && (a / b).abs() < 32767.0 {
+                    assert_close(fa / fb, a / b, &std::format!("{a} / {b}"));
+                }
+                assert_close(-fa, -a, &std::format!("-{a}"));
+            }
+        }
+    }
+
+    #[test]
+    fn functions_match_f32() {
+        for a in VALUES {
+            assert_close(Fixed16::from_f32(a.abs()).sqrt(), a.abs().sqrt(), "sqrt");
+        }
+        for i in -40..=40 {
+            let angle = i as f32 * 0.37;
+            let (sin, cos) = Fixed16::from_f32(angle).sin_cos();
+            assert_close(sin, angle.sin(), &std::format!("sin {angle}"));
+            assert_close(cos, angle.cos(), &std::format!("cos {angle}"));
+        }
+        for (num, den) in [(1, 3), (-7, 2), (255, 256), (100000, 7), (3, -1000)] {
+            assert_close(
+                Fixed16::from_ratio(num, den),
+                num as f32 / den as f32,
+                &std::format!("{num} / {den}"),
+            );
+        }
+    }
+
+    #[test]
+    fn rounding_to_integers() {
+        for (value, truncated, floor, ceil) in [
+            (2.5, 2, 2, 3),
+            (-2.5, -2, -3, -2),
+            (3.0, 3, 3, 3),
+            (-3.0, -3, -3, -3),
+            (-0.25, 0, -1, 0),
+        ] {
+            let fixed = Fixed16::from_f32(value);
+            assert_eq!(fixed.to_i32(), truncated, "{value} truncated");
+            assert_eq!(floor_i32(fixed), floor, "{value} floored");
+            assert_eq!(ceil_i32(fixed), ceil, "{value} ceiled");
+        }
+    }
+
+    #[test]
+    fn saturates() {
+        let max = Fixed16(i32::MAX);
+        let min = Fixed16(i32::MIN);
+        assert_eq!(Fixed16::from_i32(40000), max);
+        assert_eq!(Fixed16::from_i32(-40000), min);
+        assert_eq!(max + Fixed16::ONE, max);
+        assert_eq!(min - Fixed16::ONE, min);
+        assert_eq!(Fixed16::from_i32(200) * Fixed16::from_i32(200), max);
+        assert_eq!(Fixed16::ONE / Fixed16::ZERO, max);
+        assert_eq!(-Fixed16::ONE / Fixed16::ZERO, min);
+        assert_eq!(-min, max);
+        assert_eq!(Fixed16::from_ratio(1, 0), max);
+    }
+}
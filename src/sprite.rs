@@ -1,7 +1,13 @@
-use alloc::{sync::Arc, vec::Vec};
-use glam::{IVec2, U8Vec2, Vec2};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::ops::Range;
+use glam::{IVec2, U8Vec2};
 
-use crate::{BlendMode, ColorMode, Drawable, FBColor, I16Vec2, Renderer, bilinear_4_colors};
+use crate::scalar::floor_i32;
+use crate::texture::{Texels, TextureEdge};
+use crate::{
+    Affine2, BlendMode, Blender, ColorMode, Drawable, FBColor, I16Vec2, PixelFormat, Renderer,
+    Scalar, TextureFilter, U16Vec2, Vec2, bilinear_4_colors,
+};
 
 #[derive(Clone, Copy)]
 pub struct SpriteFrame {
@@ -23,23 +29,55 @@ pub enum SpriteFrameMode {
     MultipleFrames(Vec<SpriteFrame>),
 }
 
-pub struct Sprite {
+/// Transforms for each row of pixels, like [`Sprite::transform`], for effects like
+/// mode 7 floors and wavy water.
+pub enum ScanlineTransform {
+    /// Called with the `y` of each row. `Send` and `Sync`, so sprites can still be
+    /// shared between threads.
+    Callback(Box<dyn Fn(i16) -> Affine2 + Send + Sync>),
+    /// Indexed by the `y` of each row. Rows past the end aren't drawn.
+    Table(Vec<Affine2>),
+}
+
+pub struct Sprite<B = BlendMode> {
     pixels: Arc<Vec<FBColor>>,
     pub position: I16Vec2,
     pub rotation: i16,
+    /// 0x100 is the original size.
     pub scale: u16,
-    pub blend_mode: BlendMode,
+    /// Per axis, with 0x100 as the original size. Replaces `scale` when set.
+    pub scale_xy: Option<U16Vec2>,
+    /// Slants the sprite before it's rotated, moving `x` by `shear.x * y` and `y` by
+    /// `shear.y * x`.
+    pub shear: Vec2,
+    /// Replaces `rotation`, `scale`, `scale_xy` and `shear` when set. Takes texels
+    /// relative to the pivot to pixels relative to `position`.
+    pub transform: Option<Affine2>,
+    /// Replaces every other transform when set. Rows with a transform that can't be
+    /// inverted, like [`Affine2::ZERO`], aren't drawn.
+    pub scanlines: Option<ScanlineTransform>,
+    /// The point of the sprite placed at `position`, which it rotates and scales
+    /// around. Relative to the frame size, from (0, 0) at the top left to (1, 1) at
+    /// the bottom right.
+    pub pivot: Vec2,
+    /// Mirrors the sprite around its pivot.
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub blend_mode: B,
     pub modulate: ColorMode<4>,
+    /// Filtered sprites fade out over their edges instead of cutting off at them.
+    pub filter: TextureFilter,
     frame_mode: SpriteFrameMode,
     cur_frame: usize,
+    mip_levels: Vec<MipLevel>,
 }
-impl Sprite {
+impl<B: Blender> Sprite<B> {
     pub fn new(
         pixels: Arc<Vec<FBColor>>,
         position: I16Vec2,
         rotation: i16,
         scale: u16,
-        blend_mode: BlendMode,
+        blend_mode: B,
         modulate: ColorMode<4>,
         frame_mode: SpriteFrameMode,
     ) -> Self {
@@ -48,10 +86,19 @@ impl Sprite {
             position,
             rotation,
             scale,
+            scale_xy: None,
+            shear: Vec2::ZERO,
+            transform: None,
+            scanlines: None,
+            pivot: Vec2::splat(0.5),
+            flip_x: false,
+            flip_y: false,
             blend_mode,
             modulate,
+            filter: TextureFilter::Nearest,
             frame_mode,
             cur_frame: 0,
+            mip_levels: Vec::new(),
         }
     }
 
@@ -59,6 +106,32 @@ impl Sprite {
         self.cur_frame = frame;
     }
 
+    /// Builds copies of every frame at half size, then a quarter, and so on down to
+    /// a single pixel. Sprites scaled down draw from the closest of these, so they
+    /// don't shimmer as they move.
+    ///
+    /// Pixels are treated as sRGB encoded and averaged in linear light, whatever the
+    /// [`BlendSpace`](crate::BlendSpace) of the renderer they're drawn to.
+    pub fn generate_mipmaps(&mut self) {
+        self.mip_levels.clear();
+        let mut pixels: &[FBColor] = &self.pixels;
+        let mut frames = self.frames();
+        while frames.iter().any(|f| f.size.x > 1 || f.size.y > 1) {
+            let level = MipLevel::halve(pixels, &frames);
+            self.mip_levels.push(level);
+            let level = self.mip_levels.last().unwrap();
+            pixels = &level.pixels;
+            frames = level.frames.clone();
+        }
+    }
+
+    fn frames(&self) -> Vec<SpriteFrame> {
+        match &self.frame_mode {
+            SpriteFrameMode::StillImage(w, h) => alloc::vec![SpriteFrame::new(0, *w, *h)],
+            SpriteFrameMode::MultipleFrames(sprite_frames) => sprite_frames.clone(),
+        }
+    }
+
     fn size(&self) -> U8Vec2 {
         match &self.frame_mode {
             SpriteFrameMode::StillImage(w, h) => U8Vec2::new(*w, *h),
@@ -66,60 +139,293 @@ impl Sprite {
         }
     }
 
-    fn draw_rotozoom<const NO_ROTO: bool, const NO_ZOOM: bool>(
+    // pixels of the current frame at mip `level`
+    fn texels(&self, level: usize) -> (&[FBColor], U8Vec2) {
+        let (pixels, frame) = match (level, &self.frame_mode) {
+            (0, SpriteFrameMode::StillImage(w, h)) => {
+                (self.pixels.as_slice(), SpriteFrame::new(0, *w, *h))
+            }
+            (0, SpriteFrameMode::MultipleFrames(sprite_frames)) => {
+                (self.pixels.as_slice(), sprite_frames[self.cur_frame])
+            }
+            (_, SpriteFrameMode::StillImage(_, _)) => {
+                let level = &self.mip_levels[level - 1];
+                (level.pixels.as_slice(), level.frames[0])
+            }
+            (_, SpriteFrameMode::MultipleFrames(_)) => {
+                let level = &self.mip_levels[level - 1];
+                (level.pixels.as_slice(), level.frames[self.cur_frame])
+            }
+        };
+        (&pixels[frame.offset..], frame.size)
+    }
+
+    fn axis_scale(&self) -> U16Vec2 {
+        self.scale_xy.unwrap_or(U16Vec2::splat(self.scale))
+    }
+
+    fn flips<S: Scalar>(&self) -> (S, S) {
+        (
+            if self.flip_x { -S::ONE } else { S::ONE },
+            if self.flip_y { -S::ONE } else { S::ONE },
+        )
+    }
+
+    // the axes and steps of `transform`, for `map_texels`
+    fn affine_axes<S: Scalar>(&self, transform: &Affine2) -> Option<Axes<S>> {
+        let inverse = transform.matrix2.inverse();
+        if !inverse.is_finite() {
+            return None;
+        }
+        let (flip_x, flip_y) = self.flips::<S>();
+        let axes = transform
+            .matrix2
+            .to_cols_array_2d()
+            .map(|c| c.map(S::from_f32));
+        let steps = inverse.to_cols_array_2d().map(|c| c.map(S::from_f32));
+        Some(Axes {
+            axes: [
+                [axes[0][0] * flip_x, axes[0][1] * flip_x],
+                [axes[1][0] * flip_y, axes[1][1] * flip_y],
+            ],
+            steps: [
+                [steps[0][0] * flip_x, steps[0][1] * flip_y],
+                [steps[1][0] * flip_x, steps[1][1] * flip_y],
+            ],
+            translation: (
+                S::from_f32(transform.translation.x),
+                S::from_f32(transform.translation.y),
+            ),
+        })
+    }
+
+    fn map_texels<S: Scalar>(&self, axes: Axes<S>) -> Option<TexelMapping<'_, S>> {
+        let size = self.size();
+        if size.x == 0 || size.y == 0 {
+            return None;
+        }
+        let Axes {
+            axes,
+            steps,
+            translation,
+        } = axes;
+        let size_x = S::from_i32(size.x as i32);
+        let size_y = S::from_i32(size.y as i32);
+        let pivot_x = S::from_f32(self.pivot.x) * size_x;
+        let pivot_y = S::from_f32(self.pivot.y) * size_y;
+
+        // texels stepped per pixel along a row, and from one row to the next
+        let delta_row = (steps[0][0], steps[0][1]);
+        let delta_col = (steps[1][0], steps[1][1]);
+
+        // draw from the smallest mip level with texels no smaller than pixels.
+        // uv is stepped in texels of that level, which can be smaller than the sprite
+        let footprint = [delta_row, delta_col]
+            .map(|(x, y)| x.to_f32() * x.to_f32() + y.to_f32() * y.to_f32())
+            .into_iter()
+            .fold(0.0, f32::max);
+        let mut level = 0;
+        while level < self.mip_levels.len() && footprint >= (4 << (level * 2)) as f32 {
+            level += 1;
+        }
+        let (pixels, texel_size) = self.texels(level);
+        let texel_scale_x = S::from_ratio(texel_size.x as i32, size.x as i32);
+        let texel_scale_y = S::from_ratio(texel_size.y as i32, size.y as i32);
+
+        let position_x = S::from_i32(self.position.x as i32) + translation.0;
+        let position_y = S::from_i32(self.position.y as i32) + translation.1;
+
+        // the corners of the sprite on screen, past which no pixel is drawn.
+        // filters reach a little past the edges of the sprite
+        let reach = self.filter.reach_fixed16();
+        let reach_x = S::from_ratio(reach, 65536) / texel_scale_x;
+        let reach_y = S::from_ratio(reach, 65536) / texel_scale_y;
+        let mut min = (position_x, position_y);
+        let mut max = min;
+        for x in [-pivot_x - reach_x, size_x - pivot_x + reach_x] {
+            for y in [-pivot_y - reach_y, size_y - pivot_y + reach_y] {
+                let corner_x = position_x + axes[0][0] * x + axes[1][0] * y;
+                let corner_y = position_y + axes[0][1] * x + axes[1][1] * y;
+                min = (
+                    if corner_x < min.0 { corner_x } else { min.0 },
+                    if corner_y < min.1 { corner_y } else { min.1 },
+                );
+                max = (
+                    if corner_x > max.0 { corner_x } else { max.0 },
+                    if corner_y > max.1 { corner_y } else { max.1 },
+                );
+            }
+        }
+
+        let start = (
+            pivot_x - (position_x * delta_row.0 + position_y * delta_col.0),
+            pivot_y - (position_x * delta_row.1 + position_y * delta_col.1),
+        );
+
+        let start = (start.0 * texel_scale_x, start.1 * texel_scale_y);
+        let delta_col = (delta_col.0 * texel_scale_x, delta_col.1 * texel_scale_y);
+        let delta_row = (delta_row.0 * texel_scale_x, delta_row.1 * texel_scale_y);
+        Some(TexelMapping {
+            texels: Texels {
+                pixels,
+                width: texel_size.x as i32,
+                height: texel_size.y as i32,
+                edge: TextureEdge::Transparent,
+            },
+            reach,
+            start,
+            delta_row,
+            delta_col,
+            delta_row_fixed: IVec2::new(delta_row.0.to_fixed16(), delta_row.1.to_fixed16()),
+            min,
+            max,
+        })
+    }
+
+    fn draw_rotozoom<P: PixelFormat, S: Scalar, const NO_ROTO: bool, const NO_ZOOM: bool>(
         &self,
-        renderer: &mut Renderer,
+        renderer: &mut Renderer<P, S>,
         modulate_colors: &[FBColor; 4],
         single_color: bool,
     ) {
-        if self.scale == 0 {
-            return;
-        }
         let angle = if NO_ROTO {
-            0.0
+            S::ZERO
         } else {
-            ((self.rotation as f32 / 256.0) * 360.0).to_radians()
+            S::from_ratio(self.rotation as i32, 256) * S::TAU
         };
-        let scale = if NO_ZOOM {
-            1.0
+        let (scale_x, scale_y) = if NO_ZOOM {
+            (S::ONE, S::ONE)
         } else {
-            self.scale as f32 / 256.0
+            let scale = self.axis_scale();
+            (
+                S::from_ratio(scale.x as i32, 256),
+                S::from_ratio(scale.y as i32, 256),
+            )
         };
 
-        let size = self.size();
-        let pixels_offset = match &self.frame_mode {
-            SpriteFrameMode::StillImage(_, _) => 0,
-            SpriteFrameMode::MultipleFrames(sprite_frames) => sprite_frames[self.cur_frame].offset,
+        let axes = match &self.transform {
+            Some(transform) => match self.affine_axes(transform) {
+                Some(axes) => axes,
+                None => return,
+            },
+            None => {
+                // scaled, then sheared, then rotated. the inverse is worked out one
+                // step at a time, so narrow scalars keep their precision
+                let (flip_x, flip_y) = self.flips::<S>();
+                let (sin, cos) = angle.sin_cos();
+                let shear_x = S::from_f32(self.shear.x);
+                let shear_y = S::from_f32(self.shear.y);
+                let shear_det = S::ONE - shear_x * shear_y;
+                if shear_det == S::ZERO {
+                    return;
+                }
+                let x_axis = (scale_x * flip_x, shear_y * scale_x * flip_x);
+                let y_axis = (shear_x * scale_y * flip_y, scale_y * flip_y);
+                let rotate = |(x, y): (S, S)| [cos * x - sin * y, sin * x + cos * y];
+                let unscale_x = flip_x / (shear_det * scale_x);
+                let unscale_y = flip_y / (shear_det * scale_y);
+                Axes {
+                    axes: [rotate(x_axis), rotate(y_axis)],
+                    steps: [
+                        [
+                            (cos + shear_x * sin) * unscale_x,
+                            -(shear_y * cos + sin) * unscale_y,
+                        ],
+                        [
+                            (sin - shear_x * cos) * unscale_x,
+                            (cos - shear_y * sin) * unscale_y,
+                        ],
+                    ],
+                    translation: (S::ZERO, S::ZERO),
+                }
+            }
+        };
+        let Some(mapping) = self.map_texels(axes) else {
+            return;
         };
 
-        let center = size.as_vec2() / 2.0;
-
-        // crude approximation of drawing bounds
-        // faster than scanning the entire framebuffer at least
-        let diagonal_radius = ((size.x as f32).powi(2) + (size.y as f32).powi(2)).sqrt() / 2.0;
-
-        let min_x = ((self.position.x as f32 - (diagonal_radius * scale)) as i16).max(0);
-        let max_x =
-            ((self.position.x as f32 + (diagonal_radius * scale)) as i16).min(renderer.width());
-        let min_y = ((self.position.y as f32 - (diagonal_radius * scale)) as i16).max(0);
-        let max_y =
-            ((self.position.y as f32 + (diagonal_radius * scale)) as i16).min(renderer.height());
-
-        let delta_col = Vec2::new(angle.sin(), angle.cos()) / scale;
-        let delta_row = Vec2::new(delta_col.y, -delta_col.x);
-        let delta_col_fixed = (delta_col * 65536.0).as_ivec2();
-        let delta_row_fixed = (delta_row * 65536.0).as_ivec2();
-
-        let start = Vec2::new(
-            center.x
-                - (self.position.x as f32 * delta_col.y + self.position.y as f32 * delta_col.x),
-            center.y
-                - (self.position.x as f32 * delta_row.y + self.position.y as f32 * delta_row.x),
-        );
+        let (columns, rows) = mapping.bounds(renderer.clip_min(), renderer.clip_max());
+        for y in rows {
+            self.draw_row(
+                renderer,
+                &mapping,
+                y,
+                columns.clone(),
+                modulate_colors,
+                single_color,
+            );
+        }
+    }
 
-        let size_x_fixed = ((size.x as i32) << 16) as u32;
-        let size_y_fixed = ((size.y as i32) << 16) as u32;
+    fn draw_scanlines<P: PixelFormat, S: Scalar>(
+        &self,
+        renderer: &mut Renderer<P, S>,
+        scanlines: &ScanlineTransform,
+        modulate_colors: &[FBColor; 4],
+        single_color: bool,
+    ) {
+        let clip_min = renderer.clip_min();
+        let clip_max = renderer.clip_max();
+        let end_y = match scanlines {
+            ScanlineTransform::Callback(_) => clip_max.y,
+            ScanlineTransform::Table(table) => {
+                clip_max.y.min(table.len().min(i16::MAX as usize) as i16)
+            }
+        };
+        // rows usually share their matrix and differ only in translation, like wavy
+        // water, so the mapping is only worked out again when the matrix changes
+        let mut base = None;
+        for y in clip_min.y..end_y {
+            let transform = match scanlines {
+                ScanlineTransform::Callback(callback) => callback(y),
+                ScanlineTransform::Table(table) => table[y as usize],
+            };
+            if base
+                .as_ref()
+                .is_none_or(|(matrix, _)| *matrix != transform.matrix2)
+            {
+                let mapping = match self.affine_axes(&Affine2::from_mat2(transform.matrix2)) {
+                    Some(axes) => match self.map_texels(axes) {
+                        Some(mapping) => Some(mapping),
+                        None => return,
+                    },
+                    None => None,
+                };
+                base = Some((transform.matrix2, mapping));
+            }
+            let Some((_, Some(mapping))) = &base else {
+                continue;
+            };
+            let mapping = mapping.moved((
+                S::from_f32(transform.translation.x),
+                S::from_f32(transform.translation.y),
+            ));
+            let (columns, rows) = mapping.bounds(clip_min, clip_max);
+            if !rows.contains(&y) {
+                continue;
+            }
+            self.draw_row(
+                renderer,
+                &mapping,
+                y,
+                columns,
+                modulate_colors,
+                single_color,
+            );
+        }
+    }
 
+    // draws the pixels of row `y` from the first one inside of the sprite until the
+    // next one outside
+    fn draw_row<P: PixelFormat, S: Scalar>(
+        &self,
+        renderer: &mut Renderer<P, S>,
+        mapping: &TexelMapping<'_, S>,
+        y: i16,
+        xs: Range<i16>,
+        modulate_colors: &[FBColor; 4],
+        single_color: bool,
+    ) {
         /*
         we store uv as a fixed point vector instead of floating point
         the benefit of this is that instead of checking if 0 <= x and x < size,
@@ -127,82 +433,227 @@ impl Sprite {
         unsigned values. so we reduce four checks for every pixel to two casts + two checks,
         which runs faster than the c library this is benchmarked against
         (the casts are practically free since it's from signed -> unsigned int of same width)
+        filtered sprites shift uv by their reach first, to keep this a single check
         */
-        {
-            let mut row: IVec2 = ((start + delta_col * min_y as f32 + delta_row * min_x as f32)
-                * 65536.0)
-                .as_ivec2();
-
-            for y in min_y..max_y {
-                let mut uv = row;
+        let size_x = ((mapping.texels.width << 16) + mapping.reach * 2) as u32;
+        let size_y = ((mapping.texels.height << 16) + mapping.reach * 2) as u32;
+        let Range {
+            start: min_x,
+            end: max_x,
+        } = xs;
+        let mut uv = mapping.uv_at(min_x, y) + IVec2::splat(mapping.reach);
 
-                let mut x = min_x;
-                while x < max_x {
-                    if (uv.x as u32) < size_x_fixed && (uv.y as u32) < size_y_fixed {
-                        break;
-                    }
-                    uv += delta_row_fixed;
-                    x += 1;
-                }
-                loop {
-                    if x == max_x {
-                        break;
-                    }
+        let mut x = min_x;
+        while x < max_x {
+            if (uv.x as u32) < size_x && (uv.y as u32) < size_y {
+                break;
+            }
+            uv += mapping.delta_row_fixed;
+            x += 1;
+        }
+        loop {
+            if x == max_x {
+                break;
+            }
 
-                    if (uv.x as u32) < size_x_fixed && (uv.y as u32) < size_y_fixed {
-                        let c = self.pixels[pixels_offset
-                            + ((uv.y >> 16) as usize * size.x as usize + (uv.x >> 16) as usize)]
-                            * if single_color {
-                                modulate_colors[0]
-                            } else {
-                                let x = (uv.x / size.x as i32) as f32 / 65536.0;
-                                let y = (uv.y / size.y as i32) as f32 / 65536.0;
-                                bilinear_4_colors(
-                                    x,
-                                    y,
-                                    modulate_colors[0],
-                                    modulate_colors[1],
-                                    modulate_colors[2],
-                                    modulate_colors[3],
-                                )
-                            };
-                        renderer.set_unchecked(x, y, c, self.blend_mode);
+            if (uv.x as u32) < size_x && (uv.y as u32) < size_y {
+                let texel = if mapping.reach == 0 {
+                    renderer.decode_color(
+                        mapping.texels.pixels[(uv.y >> 16) as usize
+                            * mapping.texels.width as usize
+                            + (uv.x >> 16) as usize],
+                    )
+                } else {
+                    mapping.texels.sample(
+                        S::from_ratio(uv.x - mapping.reach, 65536),
+                        S::from_ratio(uv.y - mapping.reach, 65536),
+                        self.filter,
+                        |c| renderer.decode_color(c),
+                    )
+                };
+                let c = texel
+                    * if single_color {
+                        modulate_colors[0]
                     } else {
-                        break;
-                        // renderer.set(x, y, FBColor::MAGENTA_RGBA8, BlendMode::Opaque);
-                    }
+                        let x = (uv.x - mapping.reach).clamp(0, mapping.texels.width << 16);
+                        let y = (uv.y - mapping.reach).clamp(0, mapping.texels.height << 16);
+                        let x = S::from_ratio(x / mapping.texels.width, 65536);
+                        let y = S::from_ratio(y / mapping.texels.height, 65536);
+                        bilinear_4_colors(
+                            x,
+                            y,
+                            modulate_colors[0],
+                            modulate_colors[1],
+                            modulate_colors[2],
+                            modulate_colors[3],
+                        )
+                    };
+                renderer.set_unchecked(x, y, c, &self.blend_mode);
+            } else {
+                break;
+                // renderer.set(x, y, FBColor::MAGENTA_RGBA8, BlendMode::Opaque);
+            }
 
-                    uv += delta_row_fixed;
+            uv += mapping.delta_row_fixed;
 
-                    x += 1;
-                }
+            x += 1;
+        }
+    }
+}
+
+// a sprite's transform, as texels relative to the pivot to pixels relative to
+// the position (plus `translation`) and its inverse, both column major
+struct Axes<S> {
+    axes: [[S; 2]; 2],
+    steps: [[S; 2]; 2],
+    translation: (S, S),
+}
 
-                row += delta_col_fixed;
+// how pixels map onto texels of the current frame
+#[derive(Clone, Copy)]
+struct TexelMapping<'a, S> {
+    texels: Texels<'a>,
+    // how far filters reach past the edges, in 16.16 fixed point texels
+    reach: i32,
+    // the texel at pixel (0, 0), and the steps per pixel along a row and from one
+    // row to the next, in texels of the mip level being drawn
+    start: (S, S),
+    delta_row: (S, S),
+    delta_col: (S, S),
+    delta_row_fixed: IVec2,
+    // the corners of the sprite on screen
+    min: (S, S),
+    max: (S, S),
+}
+impl<S: Scalar> TexelMapping<'_, S> {
+    // the same mapping, with the sprite moved by `offset` pixels
+    fn moved(&self, offset: (S, S)) -> Self {
+        Self {
+            start: (
+                self.start.0 - offset.0 * self.delta_row.0 - offset.1 * self.delta_col.0,
+                self.start.1 - offset.0 * self.delta_row.1 - offset.1 * self.delta_col.1,
+            ),
+            min: (self.min.0 + offset.0, self.min.1 + offset.1),
+            max: (self.max.0 + offset.0, self.max.1 + offset.1),
+            ..*self
+        }
+    }
+
+    // the columns and rows of pixels inside the clip rect that the sprite can reach
+    fn bounds(&self, clip_min: I16Vec2, clip_max: I16Vec2) -> (Range<i16>, Range<i16>) {
+        let span = |min: S, max: S, clip_min: i16, clip_max: i16| {
+            let clamp = |v: i32| v.clamp(clip_min as i32, clip_max as i32) as i16;
+            clamp(floor_i32(min))..clamp(floor_i32(max) + 1)
+        };
+        (
+            span(self.min.0, self.max.0, clip_min.x, clip_max.x),
+            span(self.min.1, self.max.1, clip_min.y, clip_max.y),
+        )
+    }
+
+    fn uv_at(&self, x: i16, y: i16) -> IVec2 {
+        let x = S::from_i32(x as i32);
+        let y = S::from_i32(y as i32);
+        IVec2::new(
+            (self.start.0 + self.delta_col.0 * y + self.delta_row.0 * x).to_fixed16(),
+            (self.start.1 + self.delta_col.1 * y + self.delta_row.1 * x).to_fixed16(),
+        )
+    }
+}
+
+// every frame of a sprite, scaled down
+struct MipLevel {
+    pixels: Vec<FBColor>,
+    frames: Vec<SpriteFrame>,
+}
+impl MipLevel {
+    // averages the texels under each texel of frames half the size, weighted by
+    // how much of them it covers. premultiplied, so transparent texels don't
+    // bleed their color, and in linear light, so the levels keep the brightness
+    // of the texels they average. the texels are encoded again to be stored
+    fn halve(pixels: &[FBColor], frames: &[SpriteFrame]) -> Self {
+        let mut level = MipLevel {
+            pixels: Vec::new(),
+            frames: Vec::with_capacity(frames.len()),
+        };
+        for frame in frames {
+            let size = frame.size.max(U8Vec2::ONE);
+            let new_size = (size / 2).max(U8Vec2::ONE);
+            level.frames.push(SpriteFrame {
+                offset: level.pixels.len(),
+                size: new_size,
+            });
+            if frame.size.x == 0 || frame.size.y == 0 {
+                level.pixels.push(FBColor::EMPTY);
+                continue;
+            }
+
+            // source texels covered by `i`, with how much of each is covered
+            let covered = |i: u8, from: u8, to: u8| {
+                let start = i as f32 * from as f32 / to as f32;
+                let end = (i + 1) as f32 * from as f32 / to as f32;
+                (start as usize..(end.ceil() as usize).min(from as usize))
+                    .map(move |j| (j, end.min((j + 1) as f32) - start.max(j as f32)))
+            };
+            for y in 0..new_size.y {
+                for x in 0..new_size.x {
+                    let mut sum = [0.0; 4];
+                    let mut total = 0.0;
+                    for (src_y, weight_y) in covered(y, size.y, new_size.y) {
+                        for (src_x, weight_x) in covered(x, size.x, new_size.x) {
+                            let texel = pixels
+                                [frame.offset + src_y * frame.size.x as usize + src_x]
+                                .srgb_to_linear()
+                                .premultiplied();
+                            let weight = weight_x * weight_y;
+                            sum[0] += texel.r() * weight;
+                            sum[1] += texel.g() * weight;
+                            sum[2] += texel.b() * weight;
+                            sum[3] += texel.a() * weight;
+                            total += weight;
+                        }
+                    }
+                    let [r, g, b, a] = sum.map(|c| c / total);
+                    level
+                        .pixels
+                        .push(FBColor::new(r, g, b, a).unpremultiplied().linear_to_srgb());
+                }
             }
         }
+        level
     }
 }
-impl Drawable for Sprite {
-    fn draw(&self, renderer: &mut Renderer) {
-        let (modulate_colors, single_color) = match self.modulate {
+
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for Sprite<B> {
+    fn draw(&self, renderer: &mut Renderer<P, S>) {
+        let (modulate_colors, single_color) = match self.modulate.map(|c| renderer.decode_color(c))
+        {
             ColorMode::Solid(c) => ([c, c, c, c], true),
             ColorMode::PerPoint(cs) => (cs, false),
         };
-        match (self.rotation & 0xff, self.scale) {
-            (_, 0) => {
+        if let Some(scanlines) = &self.scanlines {
+            self.draw_scanlines(renderer, scanlines, &modulate_colors, single_color);
+            return;
+        }
+        if self.transform.is_some() {
+            self.draw_rotozoom::<P, S, true, true>(renderer, &modulate_colors, single_color);
+            return;
+        }
+        match (self.rotation & 0xff, self.axis_scale().to_array()) {
+            (_, [0, _] | [_, 0]) => {
                 return;
             }
-            (0, 0x100) => {
-                self.draw_rotozoom::<true, true>(renderer, &modulate_colors, single_color);
+            (0, [0x100, 0x100]) => {
+                self.draw_rotozoom::<P, S, true, true>(renderer, &modulate_colors, single_color);
             }
             (0, _) => {
-                self.draw_rotozoom::<true, false>(renderer, &modulate_colors, single_color);
+                self.draw_rotozoom::<P, S, true, false>(renderer, &modulate_colors, single_color);
             }
-            (_, 0x100) => {
-                self.draw_rotozoom::<false, true>(renderer, &modulate_colors, single_color);
+            (_, [0x100, 0x100]) => {
+                self.draw_rotozoom::<P, S, false, true>(renderer, &modulate_colors, single_color);
             }
             (_, _) => {
-                self.draw_rotozoom::<false, false>(renderer, &modulate_colors, single_color);
+                self.draw_rotozoom::<P, S, false, false>(renderer, &modulate_colors, single_color);
             }
         }
     }
@@ -218,12 +669,109 @@ mod tests {
     use core::ffi::*;
     use std::random::Random;
 
-    use crate::{BlendMode, I16Vec2, Renderer, ffi::*};
+    use crate::{
+        Affine2, BlendMode, I16Vec2, Rect, Renderer, TextureFilter, U16Vec2, Vec2, ffi::*,
+    };
 
-    use super::{Sprite, SpriteFrameMode};
+    use super::{ScanlineTransform, Sprite, SpriteFrameMode};
 
     const TEST_SPRITE_FILE: &[u8] = include_bytes!("../testimgs/test.gif");
 
+    fn checkerboard(size: u8) -> Sprite {
+        let pixels = (0..size as usize * size as usize)
+            .map(|i| {
+                if (i % size as usize + i / size as usize).is_multiple_of(2) {
+                    FBColor::WHITE
+                } else {
+                    FBColor::RED
+                }
+            })
+            .collect();
+        Sprite::new(
+            Arc::new(pixels),
+            I16Vec2::new(16, 12),
+            0,
+            0x100,
+            BlendMode::Opaque,
+            ColorMode::Solid(FBColor::WHITE),
+            SpriteFrameMode::StillImage(size, size),
+        )
+    }
+
+    fn pixels(renderer: &Renderer) -> Vec<[u8; 4]> {
+        renderer.rows().flatten().map(|p| p.to_rgba8()).collect()
+    }
+
+    #[test]
+    fn per_axis_scale() {
+        let draw = |sprite: &Sprite| {
+            let mut renderer = Renderer::new(32, 24);
+            sprite.draw(&mut renderer);
+            pixels(&renderer)
+        };
+        let mut sprite = checkerboard(8);
+        sprite.scale = 0x200;
+        let uniform = draw(&sprite);
+
+        // replaces `scale` when set
+        sprite.scale_xy = Some(U16Vec2::splat(0x200));
+        sprite.scale = 0x100;
+        assert_eq!(draw(&sprite), uniform);
+
+        sprite.scale_xy = Some(U16Vec2::new(0x200, 0x100));
+        let stretched = draw(&sprite);
+        sprite.scale_xy = None;
+        sprite.transform = Some(Affine2::from_scale(Vec2::new(2.0, 1.0)));
+        assert_eq!(draw(&sprite), stretched);
+        assert_ne!(stretched, uniform);
+    }
+
+    #[test]
+    fn scanlines_match_transform() {
+        let transform = |y: i16| {
+            Affine2::from_angle(0.6)
+                * Affine2::from_scale(Vec2::new(1.5, 0.8))
+                * Affine2::from_translation(Vec2::new(0.3 + y as f32 * 0.35, 0.6))
+        };
+        for filter in [TextureFilter::Nearest, TextureFilter::Bilinear] {
+            let mut sprite = checkerboard(8);
+            sprite.filter = filter;
+
+            // the same transform on every row draws like `transform`
+            let mut expected = Renderer::new(32, 24);
+            sprite.transform = Some(transform(0));
+            sprite.draw(&mut expected);
+            sprite.transform = None;
+            for scanlines in [
+                ScanlineTransform::Table(alloc::vec![transform(0); 24]),
+                ScanlineTransform::Callback(alloc::boxed::Box::new(move |_| transform(0))),
+            ] {
+                let mut renderer = Renderer::new(32, 24);
+                sprite.scanlines = Some(scanlines);
+                sprite.draw(&mut renderer);
+                assert_eq!(pixels(&renderer), pixels(&expected));
+            }
+
+            // and rows moved by their own translation draw like `transform` clipped to
+            // that row
+            let mut expected = Renderer::new(32, 24);
+            sprite.scanlines = None;
+            for y in 0..24 {
+                expected.push_clip(Rect::new(I16Vec2::new(0, y), I16Vec2::new(32, 1)));
+                sprite.transform = Some(transform(y));
+                sprite.draw(&mut expected);
+                expected.pop_clip();
+            }
+            sprite.transform = None;
+            let mut renderer = Renderer::new(32, 24);
+            sprite.scanlines = Some(ScanlineTransform::Callback(alloc::boxed::Box::new(
+                transform,
+            )));
+            sprite.draw(&mut renderer);
+            assert_eq!(pixels(&renderer), pixels(&expected));
+        }
+    }
+
     #[bench]
     fn bmp_rotozoom(bencher: &mut Bencher) {
         let src = test::black_box(unsafe {
@@ -0,0 +1,293 @@
+use alloc::vec::Vec;
+
+use crate::polygon::fill_polygon;
+use crate::scalar::ceil_i32;
+use crate::{Blender, FBColor, FillRule, PixelFormat, Renderer, Scalar};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// Which side of a shape's edge its outline grows towards.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StrokeAlignment {
+    /// Stays within the pixels the filled shape covers.
+    #[default]
+    Inner,
+    Center,
+    Outer,
+}
+impl StrokeAlignment {
+    /// How many pixels the outer and inner edges of an outline `width` wide lie
+    /// outside of the shape's edge. Centered outlines of odd widths reach further in.
+    pub(crate) fn offsets(self, width: u16) -> (i32, i32) {
+        let width = width as i32;
+        match self {
+            StrokeAlignment::Inner => (0, -width),
+            StrokeAlignment::Center => (width / 2, width / 2 - width),
+            StrokeAlignment::Outer => (width, 0),
+        }
+    }
+}
+
+// miter joins longer than this many stroke widths are beveled instead, like in svg
+const MITER_LIMIT: i32 = 4;
+
+pub(crate) type Point<S> = [S; 2];
+
+pub(crate) fn add<S: Scalar>(a: Point<S>, b: Point<S>) -> Point<S> {
+    [a[0] + b[0], a[1] + b[1]]
+}
+
+pub(crate) fn sub<S: Scalar>(a: Point<S>, b: Point<S>) -> Point<S> {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+pub(crate) fn scale<S: Scalar>(a: Point<S>, by: S) -> Point<S> {
+    [a[0] * by, a[1] * by]
+}
+
+pub(crate) fn abs<S: Scalar>(value: S) -> S {
+    if value < S::ZERO { -value } else { value }
+}
+
+// pointing to the left of `dir` on screen
+pub(crate) fn normal<S: Scalar>(dir: Point<S>) -> Point<S> {
+    [dir[1], -dir[0]]
+}
+
+/// Whether a miter join between segments along `d0` and then `d1` stays within
+/// `MITER_LIMIT`, or should be beveled instead.
+pub(crate) fn miter_fits<S: Scalar>(d0: Point<S>, d1: Point<S>) -> bool {
+    // the miter is 1 / cos(angle / 2) stroke widths long, and cos²(angle / 2) = (1 + dot) / 2
+    let dot = d0[0] * d1[0] + d0[1] * d1[1];
+    (S::ONE + dot) * S::from_i32(MITER_LIMIT * MITER_LIMIT) > S::from_i32(2)
+}
+
+/// Unit vector from `a` towards `b`, or `None` if they coincide.
+pub(crate) fn direction<S: Scalar>(a: Point<S>, b: Point<S>) -> Option<Point<S>> {
+    let d = sub(b, a);
+    // normalize by the larger component first so narrow scalars don't overflow
+    let max = if abs(d[0]) > abs(d[1]) {
+        abs(d[0])
+    } else {
+        abs(d[1])
+    };
+    if max <= S::ZERO {
+        return None;
+    }
+    let d = [d[0] / max, d[1] / max];
+    let len = (d[0] * d[0] + d[1] * d[1]).sqrt();
+    Some([d[0] / len, d[1] / len])
+}
+
+/// A convex part of a stroke. Overlapping pieces are merged when filled.
+#[derive(Clone, Copy)]
+pub(crate) enum StrokePiece<S> {
+    /// Convex quad in either winding. Triangles repeat their last point.
+    Quad([Point<S>; 4]),
+    Disk {
+        center: Point<S>,
+        radius: S,
+    },
+}
+impl<S: Scalar> StrokePiece<S> {
+    fn triangle(a: Point<S>, b: Point<S>, c: Point<S>) -> Self {
+        Self::Quad([a, b, c, c])
+    }
+
+    // the outline of the piece, always wound the same way, so a union of pieces
+    // fills as a single non-zero polygon
+    fn contour(&self) -> Vec<Point<S>> {
+        match *self {
+            StrokePiece::Quad(points) => {
+                // twice the signed area, from the shoelace formula
+                let area = (0..4).fold(S::ZERO, |area, i| {
+                    let (p, q) = (points[i], points[(i + 1) % 4]);
+                    area + p[0] * q[1] - q[0] * p[1]
+                });
+                let mut points = points.to_vec();
+                if area < S::ZERO {
+                    points.reverse();
+                }
+                points
+            }
+            StrokePiece::Disk { center, radius } => {
+                if radius <= S::ZERO {
+                    return Vec::new();
+                }
+                // enough sides to stay within a sixteenth of a pixel of the circle
+                let sides = ceil_i32(radius.sqrt() * S::from_ratio(8886, 1000)).clamp(8, 256);
+                (0..sides)
+                    .map(|i| {
+                        let (sin, cos) = (S::from_ratio(i, sides) * S::TAU).sin_cos();
+                        [center[0] + radius * cos, center[1] + radius * sin]
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Splits a stroke along `points` into convex pieces: a quad per segment, plus caps
+/// at both ends of open strokes and joins between segments.
+pub(crate) fn stroke_polyline<S: Scalar>(
+    points: &[Point<S>],
+    closed: bool,
+    half_width: S,
+    cap: LineCap,
+    join: LineJoin,
+    pieces: &mut Vec<StrokePiece<S>>,
+) {
+    // repeated points have no direction to stroke along
+    let mut deduped: Vec<Point<S>> = Vec::with_capacity(points.len());
+    for &point in points {
+        if deduped.last() != Some(&point) {
+            deduped.push(point);
+        }
+    }
+    if closed && deduped.len() > 1 && deduped.first() == deduped.last() {
+        deduped.pop();
+    }
+    let points = &deduped[..];
+
+    if points.len() == 1 {
+        // a lone point only shows up with caps that reach past it
+        let p = points[0];
+        match cap {
+            LineCap::Butt => {}
+            LineCap::Round => pieces.push(StrokePiece::Disk {
+                center: p,
+                radius: half_width,
+            }),
+            LineCap::Square => {
+                let (x, y) = ([half_width, S::ZERO], [S::ZERO, half_width]);
+                pieces.push(StrokePiece::Quad([
+                    sub(sub(p, x), y),
+                    sub(add(p, x), y),
+                    add(add(p, x), y),
+                    add(sub(p, x), y),
+                ]));
+            }
+        }
+    }
+    if points.len() < 2 {
+        return;
+    }
+
+    let segment_count = if closed {
+        points.len()
+    } else {
+        points.len() - 1
+    };
+    for i in 0..segment_count {
+        let mut a = points[i];
+        let mut b = points[(i + 1) % points.len()];
+        let Some(dir) = direction(a, b) else {
+            continue;
+        };
+        let extend = scale(dir, half_width);
+        if !closed && cap == LineCap::Square {
+            if i == 0 {
+                a = sub(a, extend);
+            }
+            if i == segment_count - 1 {
+                b = add(b, extend);
+            }
+        }
+        let n = scale(normal(dir), half_width);
+        pieces.push(StrokePiece::Quad([
+            add(a, n),
+            add(b, n),
+            sub(b, n),
+            sub(a, n),
+        ]));
+    }
+
+    if !closed && cap == LineCap::Round {
+        for center in [points[0], points[points.len() - 1]] {
+            pieces.push(StrokePiece::Disk {
+                center,
+                radius: half_width,
+            });
+        }
+    }
+
+    let joints = if closed {
+        0..points.len()
+    } else {
+        1..points.len() - 1
+    };
+    for i in joints {
+        let prev = points[(i + points.len() - 1) % points.len()];
+        let next = points[(i + 1) % points.len()];
+        join_piece(prev, points[i], next, half_width, join, pieces);
+    }
+}
+
+fn join_piece<S: Scalar>(
+    prev: Point<S>,
+    p: Point<S>,
+    next: Point<S>,
+    half_width: S,
+    join: LineJoin,
+    pieces: &mut Vec<StrokePiece<S>>,
+) {
+    if join == LineJoin::Round {
+        pieces.push(StrokePiece::Disk {
+            center: p,
+            radius: half_width,
+        });
+        return;
+    }
+    let (Some(d0), Some(d1)) = (direction(prev, p), direction(p, next)) else {
+        return;
+    };
+    let cross = d0[0] * d1[1] - d0[1] * d1[0];
+    if cross == S::ZERO {
+        return;
+    }
+
+    // the gap between the segments is on the outside of the turn
+    let side = if cross > S::ZERO {
+        half_width
+    } else {
+        -half_width
+    };
+    let n0 = scale(normal(d0), side);
+    let n1 = scale(normal(d1), side);
+    let outer0 = add(p, n0);
+    let outer1 = add(p, n1);
+
+    if join == LineJoin::Miter && miter_fits(d0, d1) {
+        let dot = d0[0] * d1[0] + d0[1] * d1[1];
+        let tip = add(p, scale(add(n0, n1), S::ONE / (S::ONE + dot)));
+        pieces.push(StrokePiece::Quad([p, outer0, tip, outer1]));
+        return;
+    }
+    pieces.push(StrokePiece::triangle(p, outer0, outer1));
+}
+
+/// Fills the union of `pieces`, sampling at pixel centers, so overlapping pieces
+/// still blend every pixel only once.
+pub(crate) fn fill_pieces<P: PixelFormat, S: Scalar, B: Blender>(
+    renderer: &mut Renderer<P, S>,
+    pieces: &[StrokePiece<S>],
+    blend_mode: &B,
+    color: impl Fn(S, S) -> FBColor,
+) {
+    let contours = pieces.iter().map(StrokePiece::contour);
+    fill_polygon(renderer, contours, FillRule::NonZero, blend_mode, color);
+}
@@ -0,0 +1,396 @@
+use core::fmt;
+
+use glam::Vec2;
+
+use crate::Path;
+
+/// Error from [`Path::from_svg`], with the byte offset in the path data where
+/// parsing stopped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SvgPathError {
+    pub position: usize,
+}
+impl fmt::Display for SvgPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid svg path data at byte {}", self.position)
+    }
+}
+impl core::error::Error for SvgPathError {}
+
+struct Parser<'a> {
+    data: &'a str,
+    pos: usize,
+}
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.data.as_bytes().get(self.pos).copied()
+    }
+
+    fn error(&self) -> SvgPathError {
+        SvgPathError { position: self.pos }
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' | b',') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Result<f32, SvgPathError> {
+        self.skip_separators();
+        let start = self.pos;
+        if let Some(b'+' | b'-') = self.peek() {
+            self.pos += 1;
+        }
+        let mut digits = self.skip_digits();
+        // a second dot starts the next number, so "0.5.5" is two numbers
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            digits += self.skip_digits();
+        }
+        if digits == 0 {
+            self.pos = start;
+            return Err(self.error());
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            let mantissa_end = self.pos;
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            if self.skip_digits() == 0 {
+                self.pos = mantissa_end;
+            }
+        }
+        self.data[start..self.pos]
+            .parse()
+            .map_err(|_| SvgPathError { position: start })
+    }
+
+    fn point(&mut self) -> Result<Vec2, SvgPathError> {
+        Ok(Vec2::new(self.number()?, self.number()?))
+    }
+
+    // flags are a single digit, and may be written without separators
+    fn flag(&mut self) -> Result<bool, SvgPathError> {
+        self.skip_separators();
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(self.error()),
+        };
+        self.pos += 1;
+        Ok(flag)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Control {
+    Cubic(Vec2),
+    Quad(Vec2),
+}
+
+impl Path {
+    /// Parses svg path data, as found in the `d` attribute of a `<path>` element.
+    pub fn from_svg(data: &str) -> Result<Self, SvgPathError> {
+        let mut parser = Parser { data, pos: 0 };
+        let mut path = Path::new();
+
+        let mut current = Vec2::ZERO;
+        let mut start = Vec2::ZERO;
+        // last control point, for the reflections in `S` and `T`
+        let mut control = None;
+        let mut command = None;
+
+        loop {
+            parser.skip_separators();
+            let Some(next) = parser.peek() else {
+                break;
+            };
+            let command_start = SvgPathError {
+                position: parser.pos,
+            };
+            if next.is_ascii_alphabetic() {
+                command = Some(next);
+                parser.pos += 1;
+            } else if matches!(command, None | Some(b'Z' | b'z')) {
+                // only commands with arguments can repeat implicitly
+                return Err(parser.error());
+            }
+            let cmd = command.unwrap();
+            if path.is_empty() && !matches!(cmd, b'M' | b'm') {
+                return Err(command_start);
+            }
+
+            let origin = if cmd.is_ascii_lowercase() {
+                current
+            } else {
+                Vec2::ZERO
+            };
+            let mut next_control = None;
+            match cmd.to_ascii_uppercase() {
+                b'M' => {
+                    current = origin + parser.point()?;
+                    start = current;
+                    path.move_to(current);
+                    // further coordinates are implicit line commands
+                    command = Some(if cmd == b'm' { b'l' } else { b'L' });
+                }
+                b'L' => {
+                    current = origin + parser.point()?;
+                    path.line_to(current);
+                }
+                b'H' => {
+                    current.x = origin.x + parser.number()?;
+                    path.line_to(current);
+                }
+                b'V' => {
+                    current.y = origin.y + parser.number()?;
+                    path.line_to(current);
+                }
+                b'C' | b'S' => {
+                    let control_1 = if cmd.eq_ignore_ascii_case(&b'C') {
+                        origin + parser.point()?
+                    } else if let Some(Control::Cubic(prev)) = control {
+                        2.0 * current - prev
+                    } else {
+                        current
+                    };
+                    let control_2 = origin + parser.point()?;
+                    current = origin + parser.point()?;
+                    path.cubic_to(control_1, control_2, current);
+                    next_control = Some(Control::Cubic(control_2));
+                }
+                b'Q' | b'T' => {
+                    let quad_control = if cmd.eq_ignore_ascii_case(&b'Q') {
+                        origin + parser.point()?
+                    } else if let Some(Control::Quad(prev)) = control {
+                        2.0 * current - prev
+                    } else {
+                        current
+                    };
+                    current = origin + parser.point()?;
+                    path.quad_to(quad_control, current);
+                    next_control = Some(Control::Quad(quad_control));
+                }
+                b'A' => {
+                    let radii = parser.point()?;
+                    let x_rotation = parser.number()?.to_radians();
+                    let large_arc = parser.flag()?;
+                    let sweep = parser.flag()?;
+                    current = origin + parser.point()?;
+                    path.arc_to(radii, x_rotation, large_arc, sweep, current);
+                }
+                b'Z' => {
+                    path.close();
+                    current = start;
+                }
+                _ => return Err(command_start),
+            }
+            control = next_control;
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+
+    use super::SvgPathError;
+    use crate::Path;
+
+    fn path(build: impl FnOnce(&mut Path)) -> Path {
+        let mut path = Path::new();
+        build(&mut path);
+        path
+    }
+
+    fn v(x: f32, y: f32) -> Vec2 {
+        Vec2::new(x, y)
+    }
+
+    fn error_at(data: &str) -> usize {
+        Path::from_svg(data).unwrap_err().position
+    }
+
+    #[test]
+    fn lines() {
+        let expected = path(|p| {
+            p.move_to(v(1.0, 2.0))
+                .line_to(v(3.0, 4.0))
+                .line_to(v(7.0, 4.0))
+                .line_to(v(7.0, -1.0))
+                .close();
+        });
+        assert_eq!(Path::from_svg("M1 2 L3 4 H7 V-1 Z").unwrap(), expected);
+        assert_eq!(Path::from_svg("m1 2 l2 2 h4 v-5 z").unwrap(), expected);
+    }
+
+    #[test]
+    fn cubics() {
+        let expected = path(|p| {
+            p.move_to(v(0.0, 0.0))
+                .cubic_to(v(1.0, 2.0), v(3.0, 4.0), v(5.0, 6.0))
+                .cubic_to(v(7.0, 8.0), v(9.0, 10.0), v(11.0, 12.0));
+        });
+        assert_eq!(
+            Path::from_svg("M0 0 C1 2 3 4 5 6 S9 10 11 12").unwrap(),
+            expected
+        );
+        assert_eq!(
+            Path::from_svg("m0 0 c1 2 3 4 5 6 s4 4 6 6").unwrap(),
+            expected
+        );
+        // without a previous cubic, the first control point is the current point
+        assert_eq!(
+            Path::from_svg("M1 1 L2 2 S3 3 4 4").unwrap(),
+            path(|p| {
+                p.move_to(v(1.0, 1.0)).line_to(v(2.0, 2.0)).cubic_to(
+                    v(2.0, 2.0),
+                    v(3.0, 3.0),
+                    v(4.0, 4.0),
+                );
+            })
+        );
+    }
+
+    #[test]
+    fn quads() {
+        let expected = path(|p| {
+            p.move_to(v(0.0, 0.0))
+                .quad_to(v(1.0, 1.0), v(2.0, 0.0))
+                .quad_to(v(3.0, -1.0), v(4.0, 0.0));
+        });
+        assert_eq!(Path::from_svg("M0 0 Q1 1 2 0 T4 0").unwrap(), expected);
+        assert_eq!(Path::from_svg("m0 0 q1 1 2 0 t2 0").unwrap(), expected);
+        // a cubic control point isn't reflected by `T`
+        assert_eq!(
+            Path::from_svg("M0 0 C1 1 2 2 3 3 T4 4").unwrap(),
+            path(|p| {
+                p.move_to(v(0.0, 0.0))
+                    .cubic_to(v(1.0, 1.0), v(2.0, 2.0), v(3.0, 3.0))
+                    .quad_to(v(3.0, 3.0), v(4.0, 4.0));
+            })
+        );
+    }
+
+    #[test]
+    fn arcs() {
+        let expected = path(|p| {
+            p.move_to(v(1.0, 1.0)).arc_to(
+                v(2.0, 3.0),
+                90f32.to_radians(),
+                true,
+                false,
+                v(5.0, 1.0),
+            );
+        });
+        assert_eq!(Path::from_svg("M1 1 A2 3 90 1 0 5 1").unwrap(), expected);
+        assert_eq!(Path::from_svg("M1 1 a2 3 90 1 0 4 0").unwrap(), expected);
+    }
+
+    #[test]
+    fn close_returns_to_start() {
+        assert_eq!(
+            Path::from_svg("M1 1 L5 1 Z l0 2").unwrap(),
+            path(|p| {
+                p.move_to(v(1.0, 1.0))
+                    .line_to(v(5.0, 1.0))
+                    .close()
+                    .line_to(v(1.0, 3.0));
+            })
+        );
+    }
+
+    #[test]
+    fn implicit_repeats() {
+        // coordinates after a move are lines
+        assert_eq!(
+            Path::from_svg("M0 0 1 1 2 2").unwrap(),
+            path(|p| {
+                p.move_to(v(0.0, 0.0))
+                    .line_to(v(1.0, 1.0))
+                    .line_to(v(2.0, 2.0));
+            })
+        );
+        assert_eq!(
+            Path::from_svg("m1 1 2 2 h1 2").unwrap(),
+            path(|p| {
+                p.move_to(v(1.0, 1.0))
+                    .line_to(v(3.0, 3.0))
+                    .line_to(v(4.0, 3.0))
+                    .line_to(v(6.0, 3.0));
+            })
+        );
+        assert_eq!(
+            Path::from_svg("M0 0 Q1 1 2 0 3 -1 4 0").unwrap(),
+            path(|p| {
+                p.move_to(v(0.0, 0.0))
+                    .quad_to(v(1.0, 1.0), v(2.0, 0.0))
+                    .quad_to(v(3.0, -1.0), v(4.0, 0.0));
+            })
+        );
+    }
+
+    #[test]
+    fn compact_numbers_and_flags() {
+        assert_eq!(
+            Path::from_svg("M.5.5-1e1-2,3E-1 4").unwrap(),
+            path(|p| {
+                p.move_to(v(0.5, 0.5))
+                    .line_to(v(-10.0, -2.0))
+                    .line_to(v(0.3, 4.0));
+            })
+        );
+        let expected = path(|p| {
+            p.move_to(v(0.0, 0.0))
+                .arc_to(v(1.0, 1.0), 0.0, false, true, v(10.0, 10.0))
+                .arc_to(v(1.0, 1.0), 0.0, true, false, v(11.0, 11.0));
+        });
+        assert_eq!(
+            Path::from_svg("M0 0a1 1 0 0110 10 1 1 0 1 0 1 1").unwrap(),
+            expected
+        );
+        assert_eq!(
+            Path::from_svg("M0,0 a1,1,0,0,1,10,10 1,1,0,10 1,1").unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert!(Path::from_svg("").unwrap().is_empty());
+        assert!(Path::from_svg(" \n ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn error_positions() {
+        // paths have to start with a move
+        assert_eq!(error_at("L1 2"), 0);
+        assert_eq!(error_at("  l1 2"), 2);
+        // missing and malformed numbers
+        assert_eq!(error_at("M1 2 L"), 6);
+        assert_eq!(error_at("M1 2 L3 -"), 8);
+        assert_eq!(error_at("M."), 1);
+        assert_eq!(error_at("M1 2 L3 e4"), 8);
+        // unknown commands
+        assert_eq!(error_at("M1 2 X3 4"), 5);
+        // numbers can't follow a close
+        assert_eq!(error_at("M1 2 Z 3 4"), 7);
+        // flags are 0 or 1
+        assert_eq!(error_at("M1 2 A1 1 0 2 1 3 4"), 12);
+        assert_eq!(
+            Path::from_svg("M1 2 X").unwrap_err(),
+            SvgPathError { position: 5 }
+        );
+    }
+}
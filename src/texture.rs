@@ -0,0 +1,158 @@
+use alloc::{sync::Arc, vec::Vec};
+
+use crate::scalar::floor_i32;
+use crate::{FBColor, Scalar, bilinear_4_colors};
+
+/// How textures are sampled between texel centers.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TextureFilter {
+    #[default]
+    Nearest,
+    Bilinear,
+    /// Catmull-Rom over the 4x4 texels around the sample, sharper than bilinear
+    /// when scaled up.
+    Bicubic,
+}
+impl TextureFilter {
+    // how far past the edges of an image samples still pick up its texels,
+    // in 16.16 fixed point texels
+    pub(crate) fn reach_fixed16(self) -> i32 {
+        match self {
+            TextureFilter::Nearest => 0,
+            TextureFilter::Bilinear => 0x8000,
+            TextureFilter::Bicubic => 0x18000,
+        }
+    }
+}
+
+// what samples see past the edges of an image
+#[derive(Clone, Copy)]
+pub(crate) enum TextureEdge {
+    // the edge texels repeat
+    Clamp,
+    // transparent texels, so images fade out over their edges
+    Transparent,
+}
+
+// a borrowed image, sampled in texels
+#[derive(Clone, Copy)]
+pub(crate) struct Texels<'a> {
+    pub pixels: &'a [FBColor],
+    pub width: i32,
+    pub height: i32,
+    pub edge: TextureEdge,
+}
+impl Texels<'_> {
+    fn texel(&self, x: i32, y: i32) -> FBColor {
+        let (x, y) = match self.edge {
+            TextureEdge::Clamp => (x.clamp(0, self.width - 1), y.clamp(0, self.height - 1)),
+            TextureEdge::Transparent => {
+                if x < 0 || y < 0 || x >= self.width || y >= self.height {
+                    return FBColor::EMPTY;
+                }
+                (x, y)
+            }
+        };
+        self.pixels[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Samples at `x`, `y` in texels, with texel centers at their halves. Texels go
+    /// through `decode` before they are filtered, so they get mixed in the
+    /// renderer's blend space.
+    pub fn sample<S: Scalar>(
+        &self,
+        x: S,
+        y: S,
+        filter: TextureFilter,
+        decode: impl Fn(FBColor) -> FBColor,
+    ) -> FBColor {
+        if self.width <= 0 || self.height <= 0 {
+            return FBColor::EMPTY;
+        }
+        if filter == TextureFilter::Nearest {
+            return decode(self.texel(floor_i32(x), floor_i32(y)));
+        }
+
+        // filters mix premultiplied texels, so transparent texels don't bleed
+        // their color into the result
+        let x = x - S::HALF;
+        let y = y - S::HALF;
+        let left = floor_i32(x);
+        let top = floor_i32(y);
+        let fract_x = x - S::from_i32(left);
+        let fract_y = y - S::from_i32(top);
+        let texel = |x, y| decode(self.texel(x, y)).premultiplied();
+        match filter {
+            TextureFilter::Nearest => unreachable!(),
+            TextureFilter::Bilinear => bilinear_4_colors(
+                fract_x,
+                fract_y,
+                texel(left, top),
+                texel(left + 1, top),
+                texel(left, top + 1),
+                texel(left + 1, top + 1),
+            )
+            .unpremultiplied(),
+            TextureFilter::Bicubic => {
+                let rows = [-1, 0, 1, 2].map(|dy| {
+                    let row = [-1, 0, 1, 2].map(|dx| texel(left + dx, top + dy));
+                    FBColor::catmull_rom(&row, fract_x)
+                });
+                // the spline overshoots around sharp edges
+                FBColor::catmull_rom(&rows, fract_y)
+                    .clamp_premultiplied()
+                    .unpremultiplied()
+            }
+        }
+    }
+}
+
+/// Pixels sampled by textured drawables, addressed from 0 to 1 across each axis.
+///
+/// Samples past the edges repeat the edge texels.
+#[derive(Clone)]
+pub struct Texture {
+    pixels: Arc<Vec<FBColor>>,
+    width: u16,
+    height: u16,
+}
+impl Texture {
+    pub fn new(pixels: Arc<Vec<FBColor>>, width: u16, height: u16) -> Self {
+        assert!(pixels.len() >= width as usize * height as usize);
+        Self {
+            pixels,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Samples the texture at `u`, `v`, decoding texels like `Texels::sample`.
+    pub(crate) fn sample<S: Scalar>(
+        &self,
+        u: S,
+        v: S,
+        filter: TextureFilter,
+        decode: impl Fn(FBColor) -> FBColor,
+    ) -> FBColor {
+        let texels = Texels {
+            pixels: &self.pixels,
+            width: self.width as i32,
+            height: self.height as i32,
+            edge: TextureEdge::Clamp,
+        };
+        texels.sample(
+            u * S::from_i32(self.width as i32),
+            v * S::from_i32(self.height as i32),
+            filter,
+            decode,
+        )
+    }
+}
@@ -1,110 +1,371 @@
-use glam::Vec2;
+use alloc::vec::Vec;
 
-use crate::{BlendMode, ColorMode, Drawable, FBColor, I16Vec2};
+use crate::scalar::{SUBPIXEL, to_subpixel};
+use crate::stroke::{
+    Point, StrokePiece, add, direction, fill_pieces, miter_fits, normal, scale, sub,
+};
+use crate::texture::{Texture, TextureFilter};
+use crate::{
+    BlendMode, Blender, ColorMode, Drawable, FBColor, I16Vec2, PixelFormat, Renderer, Scalar,
+    StrokeAlignment, Vec2,
+};
 
-fn edge(a: I16Vec2, b: I16Vec2, c: I16Vec2) -> i32 {
-    (b.x as i32 - a.x as i32) * (c.y as i32 - a.y as i32)
-        - (b.y as i32 - a.y as i32) * (c.x as i32 - a.x as i32)
+type Subpixel = [i64; 2];
+
+fn edge(a: Subpixel, b: Subpixel, c: Subpixel) -> i64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
 }
 
-pub struct Triangle {
-    pub a: I16Vec2,
-    pub b: I16Vec2,
-    pub c: I16Vec2,
+/// Vertices are in pixels, with whole numbers at pixel centers. Fractional positions
+/// are rounded to a sixteenth of a pixel.
+pub struct Triangle<B = BlendMode> {
+    pub a: Vec2,
+    pub b: Vec2,
+    pub c: Vec2,
     pub color_mode: ColorMode<3>,
-    pub blend_mode: BlendMode,
+    pub blend_mode: B,
+    pub fill: bool,
+    /// Outline width, when not filled. Outlines are beveled past very sharp corners,
+    /// like [`LineJoin::Miter`](crate::LineJoin::Miter) joins.
+    pub width: u16,
+    pub alignment: StrokeAlignment,
 }
-impl Triangle {
-    pub fn new(
-        a: I16Vec2,
-        b: I16Vec2,
-        c: I16Vec2,
-        color_mode: ColorMode<3>,
-        blend_mode: BlendMode,
-    ) -> Self {
+impl<B: Blender> Triangle<B> {
+    pub fn new(a: Vec2, b: Vec2, c: Vec2, color_mode: ColorMode<3>, blend_mode: B) -> Self {
         Self {
             a,
             b,
             c,
             color_mode,
             blend_mode,
+            fill: true,
+            width: 1,
+            alignment: StrokeAlignment::Inner,
+        }
+    }
+
+    fn draw_outline<P: PixelFormat, S: Scalar>(
+        &self,
+        renderer: &mut Renderer<P, S>,
+        colors: &[FBColor; 3],
+        single_color: bool,
+    ) {
+        // work relative to `a`, in units of the largest extent, so narrow scalars
+        // don't overflow
+        let ab = self.b - self.a;
+        let ac = self.c - self.a;
+        let unit = ab.abs().max_element().max(ac.abs().max_element());
+        if unit.is_nan() || unit <= 0.0 {
+            return;
+        }
+        let u = S::from_f32(unit);
+        let b = [S::from_f32(ab.x / unit), S::from_f32(ab.y / unit)];
+        let c = [S::from_f32(ac.x / unit), S::from_f32(ac.y / unit)];
+        let total = b[0] * c[1] - b[1] * c[0];
+        if total == S::ZERO {
+            return;
+        }
+        let length = |p: Point<S>| (p[0] * p[0] + p[1] * p[1]).sqrt();
+        let (len_a, len_b, len_c) = (length(sub(c, b)), length(c), length(b));
+        let perimeter = len_a + len_b + len_c;
+
+        // offsetting every edge of a triangle by the same distance scales it around
+        // its incenter, by how far that is from the edges
+        let incenter = scale(add(scale(b, len_b), scale(c, len_c)), S::ONE / perimeter);
+        let inradius = if total < S::ZERO { -total } else { total } / perimeter * u;
+
+        // filled triangles can include pixel centers right on their edges
+        let edge = inradius + S::from_ratio(1, 64);
+        let (outer_offset, inner_offset) = self.alignment.offsets(self.width);
+        let outer = (edge + S::from_i32(outer_offset)) / inradius;
+        let inner = (edge + S::from_i32(inner_offset)) / inradius;
+
+        let origin = [
+            S::from_f32(self.a.x) + S::HALF,
+            S::from_f32(self.a.y) + S::HALF,
+        ];
+        let place =
+            |p: Point<S>, by: S| add(origin, scale(add(incenter, scale(sub(p, incenter), by)), u));
+        let corners = [[S::ZERO; 2], b, c];
+        let outward = if total > S::ZERO { S::ONE } else { -S::ONE };
+
+        // where the outline's outer edge enters and leaves each corner. parts of the
+        // outline outside of sharp corners are beveled past the miter limit, like
+        // stroke joins, across the ends of the edges moved out
+        let outer_corners: [[Point<S>; 2]; 3] = core::array::from_fn(|i| {
+            let (prev, p, next) = (corners[(i + 2) % 3], corners[i], corners[(i + 1) % 3]);
+            let tip = place(p, outer);
+            let (Some(d0), Some(d1)) = (direction(prev, p), direction(p, next)) else {
+                return [tip; 2];
+            };
+            if outer_offset <= 0 || miter_fits(d0, d1) {
+                return [tip; 2];
+            }
+            let corner = place(p, edge / inradius);
+            let reach = S::from_i32(outer_offset) * outward;
+            let bevel = |d: Point<S>| add(corner, scale(normal(d), reach));
+            [bevel(d0), bevel(d1)]
+        });
+
+        let mut pieces = Vec::new();
+        if inner > S::ZERO {
+            let inner = corners.map(|p| place(p, inner));
+            for i in 0..3 {
+                let j = (i + 1) % 3;
+                pieces.push(StrokePiece::Quad([
+                    outer_corners[i][1],
+                    outer_corners[j][0],
+                    inner[j],
+                    inner[i],
+                ]));
+                if outer_corners[i][0] != outer_corners[i][1] {
+                    pieces.push(StrokePiece::Quad([
+                        outer_corners[i][0],
+                        outer_corners[i][1],
+                        inner[i],
+                        inner[i],
+                    ]));
+                }
+            }
+        } else {
+            // the filled outline, split into two quads
+            let [[p0, p1], [p2, p3], [p4, p5]] = outer_corners;
+            pieces.push(StrokePiece::Quad([p0, p1, p2, p3]));
+            pieces.push(StrokePiece::Quad([p0, p3, p4, p5]));
+        }
+
+        if single_color {
+            fill_pieces(renderer, &pieces, &self.blend_mode, |_, _| colors[0]);
+        } else {
+            // barycentric weights, clamped to the triangle for outer outlines
+            fill_pieces(renderer, &pieces, &self.blend_mode, |x, y| {
+                let p = [(x - origin[0]) / u, (y - origin[1]) / u];
+                let cross = |a: Point<S>, b: Point<S>| {
+                    let weight =
+                        ((b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])) / total;
+                    if weight < S::ZERO { S::ZERO } else { weight }
+                };
+                let weights = [cross(b, c), cross(c, [S::ZERO; 2]), cross([S::ZERO; 2], b)];
+                let sum = weights[0] + weights[1] + weights[2];
+                FBColor::lerp3(colors, &weights.map(|w| w / sum))
+            });
         }
     }
 }
-impl Drawable for Triangle {
-    fn draw(&self, renderer: &mut crate::Renderer) {
-        let (colors, single_color) = match self.color_mode {
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for Triangle<B> {
+    fn draw(&self, renderer: &mut crate::Renderer<P, S>) {
+        let (colors, single_color) = match self.color_mode.map(|c| renderer.decode_color(c)) {
             ColorMode::Solid(fbcolor) => ([fbcolor; 3], true),
             ColorMode::PerPoint(colors) => (colors, false),
         };
+        if !self.fill {
+            self.draw_outline(renderer, &colors, single_color);
+            return;
+        }
 
-        let a = self.a;
-        let b = self.b;
-        let c = self.c;
-        let self_edge = edge(a, b, c);
-        let flip_sign = self_edge.is_negative();
-        let self_edge = self_edge.abs() as f32;
-
-        let min_x = a.x.min(b.x).min(c.x).max(0);
-        let max_x = a.x.max(b.x).max(c.x).min(renderer.width());
-        let min_y = a.y.min(b.y).min(c.y).max(0);
-        let max_y = a.y.max(b.y).max(c.y).min(renderer.height());
-
-        'yloop: for y in min_y..max_y {
-            let mut start_x = min_x;
-            loop {
-                if start_x >= max_x {
-                    continue 'yloop;
+        rasterize(
+            self.a,
+            self.b,
+            self.c,
+            renderer.clip_min(),
+            renderer.clip_max(),
+            |x, y, weights, area| {
+                if single_color {
+                    renderer.set(x, y, colors[0], &self.blend_mode);
+                } else {
+                    let color = FBColor::lerp3(&colors, &weights.map(|w| S::from_ratio(w, area)));
+                    renderer.set(x, y, color, &self.blend_mode);
                 }
+            },
+        );
+    }
+}
 
-                let mut edge_a = edge(a, b, I16Vec2::new(start_x, y));
-                let mut edge_b = edge(b, c, I16Vec2::new(start_x, y));
-                let mut edge_c = edge(c, a, I16Vec2::new(start_x, y));
+/// Calls `plot` for every pixel whose center is inside the triangle, with the weights
+/// of `a`, `b` and `c` at that center, relative to `area`.
+///
+/// Pixels right on an edge belong to the triangle only if it's a top or left edge,
+/// so triangles sharing an edge neither overlap nor leave gaps between them.
+pub(crate) fn rasterize(
+    a: Vec2,
+    b: Vec2,
+    c: Vec2,
+    clip_min: I16Vec2,
+    clip_max: I16Vec2,
+    plot: impl FnMut(i16, i16, [i32; 3], i32),
+) {
+    let [a, b, c] = [a, b, c].map(subpixel_point);
+    rasterize_subpixel(a, b, c, clip_min, clip_max, plot);
+}
 
-                if flip_sign {
-                    edge_a = -edge_a;
-                    edge_b = -edge_b;
-                    edge_c = -edge_c;
-                }
+pub(crate) fn subpixel_point(p: Vec2) -> Subpixel {
+    [to_subpixel(p.x), to_subpixel(p.y)]
+}
 
-                if edge_a >= 0 && edge_b >= 0 && edge_c >= 0 {
-                    break;
-                }
+/// [`rasterize`] with vertices already in subpixels.
+pub(crate) fn rasterize_subpixel(
+    a: Subpixel,
+    b: Subpixel,
+    c: Subpixel,
+    clip_min: I16Vec2,
+    clip_max: I16Vec2,
+    mut plot: impl FnMut(i16, i16, [i32; 3], i32),
+) {
+    let area = edge(a, b, c);
+    if area == 0 {
+        return;
+    }
+    let flip_sign = area.is_negative();
 
-                start_x += 1;
-            }
-            for x in start_x..max_x {
-                let mut edge_a = edge(a, b, I16Vec2::new(x, y));
-                let mut edge_b = edge(b, c, I16Vec2::new(x, y));
-                let mut edge_c = edge(c, a, I16Vec2::new(x, y));
-
-                if flip_sign {
-                    edge_a = -edge_a;
-                    edge_b = -edge_b;
-                    edge_c = -edge_c;
-                }
+    // pixel centers sit on whole pixels, which are multiples of `SUBPIXEL`
+    let first_pixel = |v: i64| -(-v).div_euclid(SUBPIXEL);
+    let last_pixel = |v: i64| v.div_euclid(SUBPIXEL);
+    let min_x = first_pixel(a[0].min(b[0]).min(c[0])).max(clip_min.x as i64);
+    let max_x = (last_pixel(a[0].max(b[0]).max(c[0])) + 1).min(clip_max.x as i64);
+    let min_y = first_pixel(a[1].min(b[1]).min(c[1])).max(clip_min.y as i64);
+    let max_y = (last_pixel(a[1].max(b[1]).max(c[1])) + 1).min(clip_max.y as i64);
+    if min_x >= max_x || min_y >= max_y {
+        return;
+    }
 
-                if edge_a >= 0 && edge_b >= 0 && edge_c >= 0 {
-                    if single_color {
-                        renderer.set(x, y, colors[0], self.blend_mode);
-                    } else {
-                        let color = FBColor::lerp3(
-                            &colors,
-                            &[
-                                edge_b as f32 / self_edge as f32,
-                                edge_c as f32 / self_edge as f32,
-                                edge_a as f32 / self_edge as f32,
-                            ],
-                        );
-                        renderer.set(x, y, color, self.blend_mode);
-                    }
-                } else {
-                    break;
-                }
+    // the edges opposite of `a`, `b` and `c`, wound so the inside is positive
+    let sign = if flip_sign { -1 } else { 1 };
+    let edges = [(b, c), (c, a), (a, b)].map(|(p, q)| {
+        let (dx, dy) = ((q[0] - p[0]) * sign, (q[1] - p[1]) * sign);
+        let top_left = dy < 0 || (dy == 0 && dx > 0);
+        EdgeSetup {
+            start: edge(p, q, [min_x * SUBPIXEL, min_y * SUBPIXEL]) * sign,
+            step_x: -dy * SUBPIXEL,
+            step_y: dx * SUBPIXEL,
+            bias: if top_left { 0 } else { 1 },
+        }
+    });
+    let area = area.abs();
+    // weights inside never exceed the area, so shifting both down keeps them in an i32
+    let shift = (i64::BITS - area.leading_zeros()).saturating_sub(31);
+
+    let mut row = edges.map(|edge| edge.start);
+    for y in min_y..max_y {
+        let mut weights = row;
+        let mut entered = false;
+        for x in min_x..max_x {
+            if (0..3).all(|i| weights[i] >= edges[i].bias) {
+                entered = true;
+                plot(
+                    x as i16,
+                    y as i16,
+                    weights.map(|w| (w >> shift) as i32),
+                    (area >> shift) as i32,
+                );
+            } else if entered {
+                // triangles are convex, so nothing else on this row is inside
+                break;
             }
+            for i in 0..3 {
+                weights[i] += edges[i].step_x;
+            }
+        }
+        for i in 0..3 {
+            row[i] += edges[i].step_y;
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct EdgeSetup {
+    // edge function at the center of the top left pixel of the bounds
+    start: i64,
+    step_x: i64,
+    step_y: i64,
+    // smallest edge function value counted as inside
+    bias: i64,
+}
+
+/// Vertices are positioned like [`Triangle`]'s.
+pub struct TexturedTriangle<B = BlendMode> {
+    pub a: Vec2,
+    pub b: Vec2,
+    pub c: Vec2,
+    pub texture: Texture,
+    /// Texture coordinates at `a`, `b` and `c`.
+    pub uv: [Vec2; 3],
+    /// Perspective divisors at `a`, `b` and `c`, for perspective-correct texture
+    /// coordinates. They interpolate linearly on screen while all equal.
+    pub w: [f32; 3],
+    pub filter: TextureFilter,
+    /// Multiplied with the sampled texels.
+    pub modulate: ColorMode<3>,
+    pub blend_mode: B,
+}
+impl<B: Blender> TexturedTriangle<B> {
+    pub fn new(
+        a: Vec2,
+        b: Vec2,
+        c: Vec2,
+        texture: Texture,
+        uv: [Vec2; 3],
+        modulate: ColorMode<3>,
+        blend_mode: B,
+    ) -> Self {
+        Self {
+            a,
+            b,
+            c,
+            texture,
+            uv,
+            w: [1.0; 3],
+            filter: TextureFilter::Nearest,
+            modulate,
+            blend_mode,
         }
     }
 }
+impl<P: PixelFormat, S: Scalar, B: Blender> Drawable<P, S> for TexturedTriangle<B> {
+    fn draw(&self, renderer: &mut crate::Renderer<P, S>) {
+        let (modulate, single_color) = match self.modulate.map(|c| renderer.decode_color(c)) {
+            ColorMode::Solid(fbcolor) => ([fbcolor; 3], true),
+            ColorMode::PerPoint(colors) => (colors, false),
+        };
+        let uv = self.uv.map(|uv| [S::from_f32(uv.x), S::from_f32(uv.y)]);
+        let affine = self.w[0] == self.w[1] && self.w[1] == self.w[2];
+        let inv_w = self.w.map(|w| S::from_f32(w.recip()));
+
+        rasterize(
+            self.a,
+            self.b,
+            self.c,
+            renderer.clip_min(),
+            renderer.clip_max(),
+            |x, y, weights, area| {
+                let weights = weights.map(|w| S::from_ratio(w, area));
+                let weights = if affine {
+                    weights
+                } else {
+                    // interpolate uv / w and 1 / w, which are linear on screen
+                    let weights = [
+                        weights[0] * inv_w[0],
+                        weights[1] * inv_w[1],
+                        weights[2] * inv_w[2],
+                    ];
+                    let sum = weights[0] + weights[1] + weights[2];
+                    weights.map(|w| w / sum)
+                };
+                let u = weights[0] * uv[0][0] + weights[1] * uv[1][0] + weights[2] * uv[2][0];
+                let v = weights[0] * uv[0][1] + weights[1] * uv[1][1] + weights[2] * uv[2][1];
+                let texel = self
+                    .texture
+                    .sample(u, v, self.filter, |c| renderer.decode_color(c));
+                let color = texel
+                    * if single_color {
+                        modulate[0]
+                    } else {
+                        FBColor::lerp3(&modulate, &weights)
+                    };
+                renderer.set(x, y, color, &self.blend_mode);
+            },
+        );
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -115,9 +376,102 @@ mod tests {
     use core::ffi::*;
     use std::random::Random;
 
-    use crate::{BlendMode, I16Vec2, Renderer, ffi::*};
+    use alloc::{sync::Arc, vec};
+
+    use crate::{BlendMode, Renderer, StrokeAlignment, Texture, TextureFilter, Vec2, ffi::*};
 
-    use super::Triangle;
+    use super::{TexturedTriangle, Triangle, rasterize};
+
+    // how many times each pixel of a 16x16 grid is plotted by the given triangles
+    fn coverage(triangles: &[[Vec2; 3]]) -> [[u8; 16]; 16] {
+        let mut counts = [[0; 16]; 16];
+        for &[a, b, c] in triangles {
+            let clip_max = crate::I16Vec2::new(16, 16);
+            rasterize(a, b, c, crate::I16Vec2::ZERO, clip_max, |x, y, _, _| {
+                counts[y as usize][x as usize] += 1;
+            });
+        }
+        counts
+    }
+
+    #[test]
+    fn sharp_outlines_are_beveled() {
+        for alignment in [StrokeAlignment::Center, StrokeAlignment::Outer] {
+            let mut renderer = Renderer::new(96, 24);
+            let mut triangle = Triangle::new(
+                Vec2::new(4.0, 10.0),
+                Vec2::new(44.0, 12.0),
+                Vec2::new(4.0, 14.0),
+                ColorMode::Solid(FBColor::WHITE),
+                BlendMode::Opaque,
+            );
+            triangle.fill = false;
+            triangle.width = 3;
+            triangle.alignment = alignment;
+            triangle.draw(&mut renderer);
+            // a miter at the tip would reach about 60 pixels past it
+            let right = renderer
+                .rows()
+                .flat_map(|row| row.iter().enumerate())
+                .filter(|(_, pixel)| pixel.a() > 0.0)
+                .map(|(x, _)| x)
+                .max();
+            assert!(
+                right.is_some_and(|x| x <= 44 + 3),
+                "{alignment:?}: {right:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn shared_edges() {
+        let v = Vec2::new;
+        // on a square with corners at pixel centers, the top and left edges are drawn
+        // and the bottom and right ones aren't, whichever way the diagonal goes
+        for quad in [
+            [v(2.0, 2.0), v(10.0, 2.0), v(10.0, 10.0), v(2.0, 10.0)],
+            [v(10.0, 2.0), v(10.0, 10.0), v(2.0, 10.0), v(2.0, 2.0)],
+        ] {
+            let [a, b, c, d] = quad;
+            let counts = coverage(&[[a, b, c], [a, c, d]]);
+            for (y, row) in counts.iter().enumerate() {
+                for (x, &count) in row.iter().enumerate() {
+                    let inside = (2..10).contains(&x) && (2..10).contains(&y);
+                    assert_eq!(count, inside as u8, "pixel {x}, {y}");
+                }
+            }
+        }
+
+        // fans of triangles around a shared vertex, with edges through pixel centers
+        // at all sorts of angles, cover every pixel inside exactly once
+        for center in [v(7.5, 7.5), v(8.0, 8.0), v(7.3, 8.6)] {
+            let rim = [
+                v(1.0, 1.0),
+                v(8.0, 0.5),
+                v(15.0, 1.0),
+                v(14.25, 8.0),
+                v(15.0, 15.0),
+                v(8.0, 14.0),
+                v(1.0, 15.0),
+                v(2.0, 8.0),
+            ];
+            let fan: alloc::vec::Vec<_> = (0..rim.len())
+                .map(|i| [center, rim[i], rim[(i + 1) % rim.len()]])
+                .collect();
+            let counts = coverage(&fan);
+            for (y, row) in counts.iter().enumerate() {
+                for (x, &count) in row.iter().enumerate() {
+                    assert!(count <= 1, "pixel {x}, {y} drawn {count} times");
+                }
+            }
+            // the pixels whose centers are well inside the rim's bounding octagon
+            for (y, row) in counts.iter().enumerate().take(13).skip(3) {
+                for (x, &count) in row.iter().enumerate().take(13).skip(4) {
+                    assert_eq!(count, 1, "pixel {x}, {y} missed");
+                }
+            }
+        }
+    }
 
     #[bench]
     fn bmp_triangle(bencher: &mut Bencher) {
@@ -143,19 +497,45 @@ mod tests {
         let mut renderer = test::black_box(Renderer::new(128, 128));
         let mut rand = std::random::DefaultRandomSource;
         let mut tri = Triangle::new(
-            I16Vec2::ZERO,
-            I16Vec2::ZERO,
-            I16Vec2::ZERO,
+            Vec2::ZERO,
+            Vec2::ZERO,
+            Vec2::ZERO,
             ColorMode::Solid(FBColor::MAGENTA),
             BlendMode::Opaque,
         );
         bencher.iter(|| {
-            tri.a.x = c_short::random(&mut rand).clamp(-32, 128 + 32);
-            tri.a.y = c_short::random(&mut rand).clamp(-32, 128 + 32);
-            tri.b.x = c_short::random(&mut rand).clamp(-32, 128 + 32);
-            tri.b.y = c_short::random(&mut rand).clamp(-32, 128 + 32);
-            tri.c.x = c_short::random(&mut rand).clamp(-32, 128 + 32);
-            tri.c.y = c_short::random(&mut rand).clamp(-32, 128 + 32);
+            tri.a.x = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            tri.a.y = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            tri.b.x = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            tri.b.y = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            tri.c.x = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            tri.c.y = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            tri.draw(&mut renderer);
+        })
+    }
+
+    #[bench]
+    fn our_textured_triangle(bencher: &mut Bencher) {
+        let mut renderer = test::black_box(Renderer::new(128, 128));
+        let mut rand = std::random::DefaultRandomSource;
+        let texture = Texture::new(Arc::new(vec![FBColor::MAGENTA; 32 * 32]), 32, 32);
+        let mut tri = TexturedTriangle::new(
+            Vec2::ZERO,
+            Vec2::ZERO,
+            Vec2::ZERO,
+            texture,
+            [Vec2::ZERO, Vec2::X, Vec2::Y],
+            ColorMode::Solid(FBColor::WHITE),
+            BlendMode::Opaque,
+        );
+        tri.filter = TextureFilter::Bilinear;
+        bencher.iter(|| {
+            tri.a.x = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            tri.a.y = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            tri.b.x = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            tri.b.y = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            tri.c.x = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
+            tri.c.y = c_short::random(&mut rand).clamp(-32, 128 + 32) as f32;
             tri.draw(&mut renderer);
         })
     }